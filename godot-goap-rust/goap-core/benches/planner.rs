@@ -0,0 +1,179 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use goap_core::planner::{plan, plan_with_mode, PlanAction, SearchMode};
+use goap_core::world_state::WSProperty::Truth;
+use goap_core::world_state::{
+    CoverStatusType, DistanceToTarget, WSProperty, WorldState, WorldStateProperty as WSP,
+};
+use std::hash::{Hash, Hasher};
+
+#[derive(Debug)]
+struct BenchAction {
+    name: &'static str,
+    preconditions: WorldState,
+    effects: WorldState,
+    cost: u32,
+}
+
+impl PartialEq for BenchAction {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Hash for BenchAction {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name.hash(state);
+    }
+}
+
+impl PlanAction<()> for BenchAction {
+    fn get_action_preconditions(&self) -> &WorldState {
+        &self.preconditions
+    }
+
+    fn check_action_procedural_preconditions(&self, _action_arguments: &()) -> bool {
+        true
+    }
+
+    fn get_action_effects<'a, 'b: 'a>(&'a self, _action_arguments: &'b ()) -> &'a WorldState {
+        &self.effects
+    }
+
+    fn get_action_cost(&self, _action_arguments: &()) -> u32 {
+        self.cost
+    }
+}
+
+fn action<const N: usize, const M: usize>(
+    name: &'static str,
+    preconditions: [(WSP, WSProperty); N],
+    effects: [(WSP, WSProperty); M],
+    cost: u32,
+) -> BenchAction {
+    BenchAction {
+        name,
+        preconditions: WorldState::from(preconditions),
+        effects: WorldState::from(effects),
+        cost,
+    }
+}
+
+/// roughly mirrors fishoid's combat action set
+fn combat_actions() -> Vec<BenchAction> {
+    let close = || WSProperty::DistanceToTarget(DistanceToTarget::Close);
+    vec![
+        action(
+            "GotoTarget",
+            [(WSP::HasTarget, Truth(true))],
+            [(WSP::DistanceToTarget, close())],
+            4,
+        ),
+        action("DeployWeapon", [], [(WSP::IsWeaponArmed, Truth(true))], 1),
+        action(
+            "LoadWeapon",
+            [(WSP::IsWeaponArmed, Truth(true))],
+            [(WSP::IsWeaponLoaded, Truth(true))],
+            1,
+        ),
+        action(
+            "LookAtTarget",
+            [(WSP::HasTarget, Truth(true))],
+            [(WSP::AmILookingAtTarget, Truth(true))],
+            1,
+        ),
+        action(
+            "AttackMelee",
+            [
+                (WSP::DistanceToTarget, close()),
+                (WSP::AmILookingAtTarget, Truth(true)),
+            ],
+            [(WSP::IsTargetDead, Truth(true))],
+            5,
+        ),
+        action(
+            "AttackRanged",
+            [
+                (WSP::IsWeaponLoaded, Truth(true)),
+                (WSP::AmILookingAtTarget, Truth(true)),
+                (
+                    WSP::CoverStatus,
+                    WSProperty::CoverStatus(CoverStatusType::Covered),
+                ),
+            ],
+            [(WSP::IsTargetDead, Truth(true))],
+            2,
+        ),
+        action(
+            "GotoCover",
+            [],
+            [(
+                WSP::CoverStatus,
+                WSProperty::CoverStatus(CoverStatusType::Covered),
+            )],
+            3,
+        ),
+        action("Idle", [], [(WSP::IsIdling, Truth(true))], 1),
+        action("Patrol", [], [(WSP::IsAreaSurveyed, Truth(true))], 2),
+    ]
+}
+
+fn bench_plan(c: &mut Criterion) {
+    let actions = combat_actions();
+    let initial_state = WorldState::from([(WSP::HasTarget, Truth(true))]);
+    let goal_state = WorldState::from([(WSP::IsTargetDead, Truth(true))]);
+    c.bench_function("plan kill target", |b| {
+        b.iter(|| {
+            plan(
+                black_box(&initial_state),
+                black_box(&goal_state),
+                &actions,
+                &(),
+            )
+        })
+    });
+
+    c.bench_function("plan kill target (regressive)", |b| {
+        b.iter(|| {
+            plan_with_mode(
+                SearchMode::Regressive,
+                black_box(&initial_state),
+                black_box(&goal_state),
+                &actions,
+                &(),
+            )
+        })
+    });
+
+    let unreachable_goal = WorldState::from([(WSP::IsDead, Truth(true))]);
+    c.bench_function("plan unreachable goal", |b| {
+        b.iter(|| {
+            plan(
+                black_box(&initial_state),
+                black_box(&unreachable_goal),
+                &actions,
+                &(),
+            )
+        })
+    });
+}
+
+fn bench_world_state(c: &mut Criterion) {
+    let state = WorldState::from([
+        (WSP::HasTarget, Truth(true)),
+        (WSP::IsWeaponArmed, Truth(false)),
+        (
+            WSP::DistanceToTarget,
+            WSProperty::DistanceToTarget(DistanceToTarget::Far),
+        ),
+    ]);
+    let other = WorldState::from([
+        (WSP::HasTarget, Truth(true)),
+        (WSP::IsTargetDead, Truth(true)),
+    ]);
+    c.bench_function("count state differences", |b| {
+        b.iter(|| black_box(&state).count_state_differences(black_box(&other)))
+    });
+}
+
+criterion_group!(benches, bench_plan, bench_world_state);
+criterion_main!(benches);
@@ -0,0 +1,73 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use goap_core::types::{EntityId, Vec3};
+use goap_core::working_memory::{
+    AIStimuli, Desire, Event, FactQuery, FactQueryCheck, Knowledge, Node, WMKnowledgeType,
+    WMProperty, WorkingMemory,
+};
+
+/// memory filled with facts written by the sensors of a busy thinker
+fn busy_memory() -> WorkingMemory {
+    let mut memory = WorkingMemory::with_capacity(256);
+    for id in 0..40 {
+        memory.add_working_memory_fact(
+            WMProperty::Node(Node::Patrol {
+                ainode_id: id,
+                position: Vec3::new(id as f32, 0.0, 0.0),
+            }),
+            1.0,
+            60.0,
+        );
+    }
+    for id in 0..20 {
+        memory.add_working_memory_fact(
+            WMProperty::Knowledge(Knowledge::Character(EntityId(id), None)),
+            1.0,
+            60.0,
+        );
+        memory.add_working_memory_fact(
+            WMProperty::AIStimuli(AIStimuli::Character(EntityId(id), None)),
+            1.0,
+            60.0,
+        );
+    }
+    for id in 0..30 {
+        memory.add_working_memory_fact(WMProperty::Event(Event::GoalFailed { id }), 1.0, 60.0);
+    }
+    memory.add_working_memory_fact(WMProperty::Desire(Desire::Surprise), 1.0, 60.0);
+    memory
+}
+
+fn bench_working_memory(c: &mut Criterion) {
+    let memory = busy_memory();
+    let desire =
+        || FactQuery::new().check(FactQueryCheck::Match(WMProperty::Desire(Desire::Surprise)));
+    let character = || {
+        FactQuery::with_check(FactQueryCheck::Match(WMProperty::Knowledge(
+            Knowledge::Character(EntityId(15), None),
+        )))
+    };
+    let characters =
+        || FactQuery::with_check(FactQueryCheck::Knowledge(WMKnowledgeType::Character));
+
+    c.bench_function("find desire (indexed)", |b| {
+        b.iter(|| black_box(memory.find_fact(desire()).is_some()))
+    });
+    c.bench_function("find desire (scan)", |b| {
+        b.iter(|| black_box(memory.scan_facts(desire()).next().is_some()))
+    });
+    c.bench_function("find character (indexed)", |b| {
+        b.iter(|| black_box(memory.find_fact(character()).is_some()))
+    });
+    c.bench_function("find character (scan)", |b| {
+        b.iter(|| black_box(memory.scan_facts(character()).next().is_some()))
+    });
+    c.bench_function("count characters (indexed)", |b| {
+        b.iter(|| black_box(memory.count_facts(characters())))
+    });
+    c.bench_function("count characters (scan)", |b| {
+        b.iter(|| black_box(memory.scan_facts(characters()).count()))
+    });
+}
+
+criterion_group!(benches, bench_working_memory);
+criterion_main!(benches);
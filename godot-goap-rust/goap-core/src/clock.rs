@@ -0,0 +1,57 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+
+/// source of the game time (in seconds) used by the AI instead of the wall-clock time.
+/// Advanced manually every physics frame, so it stops while the game is paused and follows the time scale.
+/// Clones share the same time.
+#[derive(Debug, Clone, Default)]
+pub struct GameClock {
+    /// bits of the current time
+    time: Arc<AtomicU64>,
+}
+
+impl GameClock {
+    /// clock shared by the whole game
+    pub fn global() -> GameClock {
+        static GLOBAL: OnceLock<GameClock> = OnceLock::new();
+        GLOBAL.get_or_init(GameClock::default).clone()
+    }
+
+    pub fn now(&self) -> f64 {
+        f64::from_bits(self.time.load(Ordering::Acquire))
+    }
+
+    pub fn advance(&self, delta: f64) {
+        let _ = self
+            .time
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |time| {
+                Some((f64::from_bits(time) + delta).to_bits())
+            });
+    }
+
+    pub fn set(&self, time: f64) {
+        self.time.store(time.to_bits(), Ordering::Release);
+    }
+
+    /// seconds passed since given time
+    pub fn elapsed_since(&self, time: f64) -> f64 {
+        self.now() - time
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_shared_time() {
+        let clock = GameClock::default();
+        let other = clock.clone();
+        clock.advance(0.5);
+        other.advance(0.25);
+        assert_eq!(clock.now(), 0.75);
+        assert_eq!(other.elapsed_since(0.5), 0.25);
+        // independent clocks don't affect each other
+        assert_eq!(GameClock::default().now(), 0.0);
+    }
+}
@@ -0,0 +1,11 @@
+//! Engine-independent part of the AI – planner, world state and working memory.
+//! Doesn't depend on godot, so it can be tested & benchmarked with plain `cargo test` / `cargo bench`.
+
+pub mod clock;
+pub mod plan_cache;
+pub mod planner;
+pub mod regressive_planner;
+mod search;
+pub mod types;
+pub mod working_memory;
+pub mod world_state;
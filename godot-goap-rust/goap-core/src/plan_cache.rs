@@ -0,0 +1,254 @@
+use crate::planner::{ActionBinding, PlanAction, PlanStep, SearchMode};
+use crate::world_state::WorldState;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// plan stored as indexes of actions in their action set. None if no plan could be found.
+pub type CachedPlan = Option<Vec<(usize, Option<ActionBinding>)>>;
+
+/// identifies a planning request.
+/// The planning context is reduced to a 64-bit hash, so two different requests can collide
+/// and reuse each other's plan. Cached plans are monitored like any other plan while they run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PlanCacheKey {
+    /// address of the shared action set
+    action_set: usize,
+    goal: usize,
+    /// both search modes can break ties between equally cheap plans differently
+    search_mode: SearchMode,
+    /// hash of the goal state, the relevant part of the world state and the results of procedural checks
+    context_hash: u64,
+}
+
+impl PlanCacheKey {
+    pub fn new<T: PlanAction<U>, U>(
+        actions: &[T],
+        goal: usize,
+        search_mode: SearchMode,
+        goal_state: &WorldState,
+        world_state: &WorldState,
+        action_arguments: &U,
+    ) -> Self {
+        let mut hasher = DefaultHasher::new();
+        goal_state.hash(&mut hasher);
+        // properties that are neither read nor written by any action nor required by the goal can't change the plan
+        for (idx, property) in world_state.inner.iter().enumerate() {
+            let is_relevant = goal_state.inner[idx].is_some()
+                || actions.iter().any(|action| {
+                    action.get_action_preconditions().inner[idx].is_some()
+                        || action.get_action_effects(action_arguments).inner[idx].is_some()
+                });
+            if is_relevant {
+                (idx, property).hash(&mut hasher);
+            }
+        }
+        // procedural preconditions, costs & bindings depend on the runtime context
+        for action in actions.iter() {
+            action
+                .check_action_procedural_preconditions(action_arguments)
+                .hash(&mut hasher);
+            action.get_action_cost(action_arguments).hash(&mut hasher);
+            action
+                .get_action_bindings(action_arguments)
+                .hash(&mut hasher);
+        }
+        PlanCacheKey {
+            action_set: actions.as_ptr() as usize,
+            goal,
+            search_mode,
+            context_hash: hasher.finish(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct CacheEntry {
+    plan: CachedPlan,
+    last_used: u64,
+}
+
+/// least-recently-used cache of plans shared by all the thinkers
+#[derive(Debug)]
+pub struct PlanCache {
+    capacity: usize,
+    entries: HashMap<PlanCacheKey, CacheEntry>,
+    /// incremented on every access, used to find the least recently used entry
+    tick: u64,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+impl Default for PlanCache {
+    fn default() -> Self {
+        PlanCache::with_capacity(256)
+    }
+}
+
+impl PlanCache {
+    pub fn with_capacity(capacity: usize) -> Self {
+        PlanCache {
+            capacity,
+            entries: HashMap::with_capacity(capacity),
+            tick: 0,
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    pub fn get(&mut self, key: &PlanCacheKey) -> Option<CachedPlan> {
+        self.tick += 1;
+        let Some(entry) = self.entries.get_mut(key) else {
+            self.misses += 1;
+            return None;
+        };
+        self.hits += 1;
+        entry.last_used = self.tick;
+        Some(entry.plan.clone())
+    }
+
+    pub fn insert(&mut self, key: PlanCacheKey, plan: CachedPlan) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            if let Some(least_recently_used) = self
+                .entries
+                .iter()
+                .min_by_key(|(_key, entry)| entry.last_used)
+                .map(|(key, _entry)| *key)
+            {
+                self.entries.remove(&least_recently_used);
+            }
+        }
+        self.tick += 1;
+        self.entries.insert(
+            key,
+            CacheEntry {
+                plan,
+                last_used: self.tick,
+            },
+        );
+    }
+
+    /// removes all the entries, e.g. after action sets have been reloaded
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// converts found plan into indexes of its actions
+pub fn to_cached_plan<T>(
+    actions: &[T],
+    plan: &[PlanStep<'_, T>],
+) -> Vec<(usize, Option<ActionBinding>)> {
+    plan.iter()
+        .filter_map(|step| {
+            let index = actions
+                .iter()
+                .position(|action| std::ptr::eq(action, step.action))?;
+            Some((index, step.binding))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::planner::plan;
+    use crate::planner::test::test_case;
+    use crate::world_state::WSProperty::Truth;
+    use crate::world_state::WorldStateProperty;
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_lru_eviction() {
+        let case = test_case("attack_melee.ron");
+        let mut cache = PlanCache::with_capacity(2);
+        let keys: Vec<PlanCacheKey> = (0..3)
+            .map(|goal| {
+                PlanCacheKey::new(
+                    &case.actions,
+                    goal,
+                    SearchMode::Forward,
+                    &case.goal_state,
+                    &case.initial_state,
+                    &case.blocked_actions,
+                )
+            })
+            .collect();
+        cache.insert(keys[0], None);
+        cache.insert(keys[1], None);
+        assert!(cache.get(&keys[0]).is_some());
+        // keys[1] is the least recently used one
+        cache.insert(keys[2], None);
+        assert_eq!(cache.len(), 2);
+        assert!(cache.get(&keys[1]).is_none());
+        assert!(cache.get(&keys[0]).is_some());
+        assert_eq!((cache.hits, cache.misses), (2, 1));
+    }
+
+    #[test]
+    fn test_cache_key() {
+        let case = test_case("attack_melee.ron");
+        let key = |state: &WorldState, blocked_actions: &HashSet<String>| {
+            PlanCacheKey::new(
+                &case.actions,
+                0,
+                SearchMode::Forward,
+                &case.goal_state,
+                state,
+                blocked_actions,
+            )
+        };
+        let base_key = key(&case.initial_state, &case.blocked_actions);
+        // property not used by any action nor the goal
+        let mut irrelevant_change = case.initial_state.clone();
+        irrelevant_change[WorldStateProperty::IsAreaSurveyed] = Some(Truth(true));
+        assert_eq!(base_key, key(&irrelevant_change, &case.blocked_actions));
+
+        let mut relevant_change = case.initial_state.clone();
+        relevant_change[WorldStateProperty::IsTargetDead] = Some(Truth(true));
+        assert_ne!(base_key, key(&relevant_change, &case.blocked_actions));
+
+        let blocked_actions = case.actions.iter().map(|a| a.name.clone()).collect();
+        assert_ne!(base_key, key(&case.initial_state, &blocked_actions));
+
+        let regressive_key = PlanCacheKey::new(
+            &case.actions,
+            0,
+            SearchMode::Regressive,
+            &case.goal_state,
+            &case.initial_state,
+            &case.blocked_actions,
+        );
+        assert_ne!(base_key, regressive_key);
+    }
+
+    #[test]
+    fn test_to_cached_plan() {
+        let case = test_case("attack_melee.ron");
+        let steps = plan(
+            &case.initial_state,
+            &case.goal_state,
+            &case.actions,
+            &case.blocked_actions,
+        )
+        .unwrap();
+        let cached = to_cached_plan(&case.actions, &steps);
+        let names: Vec<&str> = cached
+            .iter()
+            .map(|(idx, _binding)| case.actions[*idx].name.as_str())
+            .collect();
+        let expected: Vec<&str> = steps.iter().map(|s| s.action.name.as_str()).collect();
+        assert_eq!(names, expected);
+    }
+}
@@ -0,0 +1,568 @@
+use crate::regressive_planner::plan_regressive;
+use crate::search::{astar_with_budget, SearchOutcome};
+use crate::types::EntityId;
+use crate::world_state::WorldState;
+use serde::{Deserialize, Serialize};
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+pub use crate::search::PlanBudget;
+
+/// a generic trait that must be implemented by any GoapAction
+/// Arithmetic effects (`WSProperty::Add`, `WSProperty::Sub`) create a new state every time they are applied,
+/// so they should be bounded by the action preconditions (or the search budget).
+pub trait PlanAction<U>: PartialEq<Self> + Hash + Debug {
+    fn get_action_preconditions(&self) -> &WorldState;
+    fn check_action_procedural_preconditions(&self, action_arguments: &U) -> bool;
+    fn get_action_effects<'a, 'b: 'a>(&'a self, action_arguments: &'b U) -> &'a WorldState;
+    fn get_action_cost(&self, action_arguments: &U) -> u32;
+    /// candidates for the symbolic action parameter with their additional cost.
+    /// Returns None if action has no parameter; parameterised action without any candidate can't be planned.
+    #[allow(unused_variables)]
+    fn get_action_bindings(&self, action_arguments: &U) -> Option<Vec<(ActionBinding, u32)>> {
+        None
+    }
+    /// actions that are only performed as a part of other actions are never picked by the planner
+    fn is_action_plannable(&self) -> bool {
+        true
+    }
+}
+
+/// value bound to the action parameter at plan time
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ActionBinding {
+    /// index of an attack
+    Attack(usize),
+    /// id of an AINode
+    Node(u32),
+    /// item or object
+    Entity(EntityId),
+}
+
+/// binds the action parameter to its cheapest candidate.
+/// Returns the binding together with the total cost of an action,
+/// or None if there is nothing to bind or the action can't be planned at all.
+pub(crate) fn bind_action<T: PlanAction<U>, U>(
+    action: &T,
+    action_arguments: &U,
+) -> Option<(Option<ActionBinding>, u32)> {
+    if !action.is_action_plannable() {
+        return None;
+    }
+    let cost = action.get_action_cost(action_arguments);
+    let Some(bindings) = action.get_action_bindings(action_arguments) else {
+        return Some((None, cost));
+    };
+    bindings
+        .into_iter()
+        .min_by_key(|(_binding, binding_cost)| *binding_cost)
+        .map(|(binding, binding_cost)| (Some(binding), cost + binding_cost))
+}
+
+/// single step of a plan – an action with its parameter bound by the planner
+#[derive(Debug)]
+pub struct PlanStep<'a, T> {
+    pub action: &'a T,
+    pub binding: Option<ActionBinding>,
+}
+
+impl<T> Clone for PlanStep<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for PlanStep<'_, T> {}
+
+#[derive(Debug)]
+pub struct PlanNode<'a, T: PlanAction<U>, U> {
+    pub current_state: WorldState,
+    pub action: Option<&'a T>,
+    pub binding: Option<ActionBinding>,
+    extra_action_arguments: PhantomData<U>,
+}
+
+impl<'a, T: PlanAction<U>, U> Clone for PlanNode<'a, T, U> {
+    fn clone(&self) -> Self {
+        PlanNode {
+            current_state: self.current_state.clone(),
+            action: self.action,
+            binding: self.binding,
+            extra_action_arguments: PhantomData,
+        }
+    }
+}
+
+impl<T: PlanAction<U>, U> Eq for PlanNode<'_, T, U> {}
+
+impl<'a, T: PlanAction<U>, U> PartialEq<Self> for PlanNode<'a, T, U> {
+    fn eq(&self, other: &Self) -> bool {
+        // nodes reached by the same action must be distinguished by state as well,
+        // otherwise they might be merged if their hashes collide
+        if let (Some(action), Some(other_action)) = (self.action, other.action) {
+            return action == other_action
+                && self.binding == other.binding
+                && self.current_state.inner == other.current_state.inner;
+        }
+        false
+    }
+}
+
+impl<T: PlanAction<U>, U> Hash for PlanNode<'_, T, U> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        if let Some(action) = self.action.as_ref() {
+            action.hash(state);
+        }
+        self.binding.hash(state);
+        self.current_state.hash(state);
+    }
+}
+
+impl<'a, T: PlanAction<U>, U> PlanNode<'a, T, U> {
+    pub fn initial(initial_state: &WorldState) -> PlanNode<'_, T, U> {
+        PlanNode {
+            current_state: initial_state.clone(),
+            action: None,
+            binding: None,
+            extra_action_arguments: PhantomData,
+        }
+    }
+
+    pub fn child(
+        parent_state: WorldState,
+        action_type: &'a T,
+        binding: Option<ActionBinding>,
+        action_arguments: &'a U,
+    ) -> PlanNode<'a, T, U> {
+        let mut child = PlanNode {
+            current_state: parent_state.clone(),
+            action: Some(action_type),
+            binding,
+            extra_action_arguments: PhantomData,
+        };
+        child
+            .current_state
+            .apply_world_state(action_type.get_action_effects(action_arguments));
+        child
+    }
+
+    pub fn neighbours(
+        &self,
+        actions: &'a [T],
+        action_arguments: &'a U,
+    ) -> Vec<(PlanNode<'a, T, U>, u32)> {
+        let actions = actions
+            .iter()
+            .filter_map(|action| {
+                // effects are already satisfied in current state
+                if action
+                    .get_action_effects(action_arguments)
+                    .count_unsatisfied_world_state_props(&self.current_state)
+                    == 0
+                {
+                    return None;
+                }
+
+                // preconditions are not met
+                if action
+                    .get_action_preconditions()
+                    .count_unsatisfied_world_state_props(&self.current_state)
+                    != 0
+                {
+                    return None;
+                }
+
+                if !action.check_action_procedural_preconditions(action_arguments) {
+                    return None;
+                }
+
+                let (binding, cost) = bind_action(action, action_arguments)?;
+
+                Some((
+                    PlanNode::child(
+                        self.current_state.clone(),
+                        action,
+                        binding,
+                        action_arguments,
+                    ),
+                    cost,
+                ))
+            })
+            .collect();
+        actions
+    }
+}
+
+/// search strategy used by the planner
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(
+    feature = "godot",
+    derive(godot::prelude::GodotConvert, godot::prelude::Var, godot::prelude::Export),
+    godot(via = u32)
+)]
+pub enum SearchMode {
+    /// A* from the current world state towards the goal
+    #[default]
+    Forward,
+    /// backward chaining from the goal state, expands only actions relevant to unmet conditions
+    Regressive,
+}
+
+/// outcome of a plan search
+#[derive(Debug)]
+pub enum PlanResult<'a, T> {
+    Found {
+        plan: Vec<PlanStep<'a, T>>,
+        nodes_expanded: u32,
+    },
+    /// goal can't be reached with given set of actions
+    NoPlan { nodes_expanded: u32 },
+    /// search has been stopped before finding a plan.
+    /// Partial plan leads to the state closest to the goal that has been found so far.
+    BudgetExceeded {
+        partial_plan: Vec<PlanStep<'a, T>>,
+        nodes_expanded: u32,
+    },
+}
+
+impl<'a, T> PlanResult<'a, T> {
+    pub fn nodes_expanded(&self) -> u32 {
+        match self {
+            PlanResult::Found { nodes_expanded, .. }
+            | PlanResult::NoPlan { nodes_expanded }
+            | PlanResult::BudgetExceeded { nodes_expanded, .. } => *nodes_expanded,
+        }
+    }
+
+    /// returns a plan if one has been found
+    pub fn into_plan(self) -> Option<Vec<PlanStep<'a, T>>> {
+        match self {
+            PlanResult::Found { plan, .. } => Some(plan),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn from_search<N>(
+        search: (SearchOutcome<N>, u32),
+        into_plan: impl Fn(Vec<N>) -> Vec<PlanStep<'a, T>>,
+    ) -> Self {
+        let (outcome, nodes_expanded) = search;
+        match outcome {
+            SearchOutcome::Found(path) => PlanResult::Found {
+                plan: into_plan(path),
+                nodes_expanded,
+            },
+            SearchOutcome::Exhausted => PlanResult::NoPlan { nodes_expanded },
+            SearchOutcome::BudgetExceeded(path) => PlanResult::BudgetExceeded {
+                partial_plan: into_plan(path),
+                nodes_expanded,
+            },
+        }
+    }
+}
+
+pub fn plan_with_budget<'a, T: PlanAction<U>, U>(
+    search_mode: SearchMode,
+    budget: &PlanBudget,
+    initial_state: &'a WorldState,
+    goal_state: &'a WorldState,
+    actions: &'a [T],
+    action_arguments: &'a U,
+) -> PlanResult<'a, T> {
+    match search_mode {
+        SearchMode::Forward => {
+            plan_forward(initial_state, goal_state, actions, action_arguments, budget)
+        }
+        SearchMode::Regressive => {
+            plan_regressive(initial_state, goal_state, actions, action_arguments, budget)
+        }
+    }
+}
+
+/// plans without any budget
+pub fn plan_with_mode<'a, T: PlanAction<U>, U>(
+    search_mode: SearchMode,
+    initial_state: &'a WorldState,
+    goal_state: &'a WorldState,
+    actions: &'a [T],
+    action_arguments: &'a U,
+) -> Option<Vec<PlanStep<'a, T>>> {
+    plan_with_budget(
+        search_mode,
+        &PlanBudget::default(),
+        initial_state,
+        goal_state,
+        actions,
+        action_arguments,
+    )
+    .into_plan()
+}
+
+pub fn plan<'a, T: PlanAction<U>, U>(
+    initial_state: &'a WorldState,
+    goal_state: &'a WorldState,
+    actions: &'a [T],
+    action_arguments: &'a U,
+) -> Option<Vec<PlanStep<'a, T>>> {
+    plan_forward(
+        initial_state,
+        goal_state,
+        actions,
+        action_arguments,
+        &PlanBudget::default(),
+    )
+    .into_plan()
+}
+
+pub fn plan_forward<'a, T: PlanAction<U>, U>(
+    initial_state: &'a WorldState,
+    goal_state: &'a WorldState,
+    actions: &'a [T],
+    action_arguments: &'a U,
+    budget: &PlanBudget,
+) -> PlanResult<'a, T> {
+    let start = PlanNode::initial(initial_state);
+    let search = astar_with_budget(
+        &start,
+        |node| node.neighbours(actions, action_arguments),
+        |node| node.current_state.count_state_differences(goal_state),
+        |node| goal_state.count_unsatisfied_world_state_props(&node.current_state) == 0,
+        budget,
+    );
+    // skip a root node (deferred from a goal)
+    PlanResult::from_search(search, |path| {
+        path.into_iter()
+            .skip(1)
+            .map(|node| PlanStep {
+                action: node.action.unwrap(),
+                binding: node.binding,
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::*;
+    use serde::Deserialize;
+    use std::collections::HashSet;
+    use std::fs;
+    use std::path::{Path, PathBuf};
+    use std::time::Duration;
+
+    #[derive(Deserialize, Debug)]
+    pub(crate) struct TestAction {
+        pub(crate) name: String,
+        preconditions: WorldState,
+        effects: WorldState,
+        cost: u32,
+        #[serde(default)]
+        bindings: Option<Vec<(ActionBinding, u32)>>,
+        #[serde(default = "is_plannable_default")]
+        is_plannable: bool,
+    }
+
+    fn is_plannable_default() -> bool {
+        true
+    }
+
+    impl PartialEq<Self> for TestAction {
+        fn eq(&self, other: &Self) -> bool {
+            self.name == other.name
+        }
+    }
+
+    impl Hash for TestAction {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.name.hash(state);
+        }
+    }
+
+    /// action arguments are the names of actions which procedural preconditions fail
+    impl PlanAction<HashSet<String>> for TestAction {
+        fn get_action_preconditions(&self) -> &WorldState {
+            &self.preconditions
+        }
+
+        fn check_action_procedural_preconditions(&self, blocked_actions: &HashSet<String>) -> bool {
+            !blocked_actions.contains(&self.name)
+        }
+
+        fn get_action_effects<'a, 'b: 'a>(
+            &'a self,
+            _action_arguments: &'b HashSet<String>,
+        ) -> &'a WorldState {
+            &self.effects
+        }
+
+        fn get_action_cost(&self, _action_arguments: &HashSet<String>) -> u32 {
+            self.cost
+        }
+
+        fn get_action_bindings(
+            &self,
+            _action_arguments: &HashSet<String>,
+        ) -> Option<Vec<(ActionBinding, u32)>> {
+            self.bindings.clone()
+        }
+
+        fn is_action_plannable(&self) -> bool {
+            self.is_plannable
+        }
+    }
+
+    #[derive(Deserialize)]
+    pub(crate) struct TestCase {
+        #[serde(skip_deserializing)]
+        case_name: String,
+        pub(crate) actions: Vec<TestAction>,
+        #[serde(default)]
+        pub(crate) blocked_actions: HashSet<String>,
+        pub(crate) initial_state: WorldState,
+        pub(crate) goal_state: WorldState,
+        /// None if no plan should be found
+        expected_actions: Option<Vec<String>>,
+        /// checked only if given
+        #[serde(default)]
+        expected_bindings: Option<Vec<Option<ActionBinding>>>,
+    }
+
+    impl TestCase {
+        fn from_test_file(path: &Path) -> TestCase {
+            let content = fs::read_to_string(path).unwrap();
+            let mut case: TestCase =
+                ron::from_str(&content).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+            case.case_name = String::from(path.file_name().unwrap().to_str().unwrap());
+            case
+        }
+
+        /// returns an error message if plan differs from the expected one
+        fn check_plan(&self, search_mode: SearchMode) -> Result<(), String> {
+            let plan = plan_with_mode(
+                search_mode,
+                &self.initial_state,
+                &self.goal_state,
+                &self.actions,
+                &self.blocked_actions,
+            );
+            let action_names: Option<Vec<String>> = plan
+                .as_ref()
+                .map(|steps| steps.iter().map(|step| step.action.name.clone()).collect());
+            if self.expected_actions != action_names {
+                return Err(format!(
+                    "{} ({:?}) failed: expected {:?}, got {:?}",
+                    self.case_name, search_mode, self.expected_actions, action_names
+                ));
+            }
+            let Some(expected_bindings) = self.expected_bindings.as_ref() else {
+                return Ok(());
+            };
+            let bindings: Vec<Option<ActionBinding>> = plan
+                .unwrap_or_default()
+                .iter()
+                .map(|step| step.binding)
+                .collect();
+            if *expected_bindings != bindings {
+                return Err(format!(
+                    "{} ({:?}) failed: expected bindings {:?}, got {:?}",
+                    self.case_name, search_mode, expected_bindings, bindings
+                ));
+            }
+            Ok(())
+        }
+    }
+
+    pub(crate) fn test_case(name: &str) -> TestCase {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("test_data/plan_test")
+            .join(name);
+        TestCase::from_test_file(&path)
+    }
+
+    #[test]
+    fn test_budget_exceeded() {
+        let case = test_case("attack_from_cover.ron");
+        for mode in [SearchMode::Forward, SearchMode::Regressive] {
+            let result = plan_with_budget(
+                mode,
+                &PlanBudget::default().with_max_nodes(2),
+                &case.initial_state,
+                &case.goal_state,
+                &case.actions,
+                &case.blocked_actions,
+            );
+            let PlanResult::BudgetExceeded {
+                partial_plan,
+                nodes_expanded,
+            } = result
+            else {
+                panic!(
+                    "{:?}: expected budget to be exceeded, got {:?}",
+                    mode, result
+                );
+            };
+            assert_eq!(nodes_expanded, 2);
+            assert!(partial_plan.len() <= 1);
+        }
+    }
+
+    #[test]
+    fn test_time_budget_exceeded() {
+        let case = test_case("attack_from_cover.ron");
+        let result = plan_with_budget(
+            SearchMode::Forward,
+            &PlanBudget::default().with_max_time(Duration::ZERO),
+            &case.initial_state,
+            &case.goal_state,
+            &case.actions,
+            &case.blocked_actions,
+        );
+        assert!(matches!(
+            result,
+            PlanResult::BudgetExceeded {
+                nodes_expanded: 0,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_no_plan_within_budget() {
+        let case = test_case("unreachable_goal.ron");
+        let result = plan_with_budget(
+            SearchMode::Forward,
+            &PlanBudget::default().with_max_nodes(64),
+            &case.initial_state,
+            &case.goal_state,
+            &case.actions,
+            &case.blocked_actions,
+        );
+        let PlanResult::NoPlan { nodes_expanded } = result else {
+            panic!("expected no plan, got {:?}", result);
+        };
+        assert!(nodes_expanded > 0);
+    }
+
+    #[test]
+    fn run_test_files() {
+        let test_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("test_data/plan_test");
+        let mut paths: Vec<PathBuf> = fs::read_dir(test_dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.extension().map(|ext| ext == "ron").unwrap_or(false))
+            .collect();
+        paths.sort();
+        assert!(!paths.is_empty(), "no planner test cases found");
+        let failures: Vec<String> = paths
+            .iter()
+            .map(|path| TestCase::from_test_file(path))
+            .flat_map(|case| {
+                [SearchMode::Forward, SearchMode::Regressive]
+                    .into_iter()
+                    .filter_map(move |mode| case.check_plan(mode).err())
+            })
+            .collect();
+        assert!(failures.is_empty(), "{}", failures.join("\n"));
+    }
+}
@@ -0,0 +1,189 @@
+use crate::planner::{bind_action, PlanAction, PlanResult, PlanStep};
+use crate::search::{astar_with_budget, PlanBudget};
+use crate::world_state::{WSProperty, WorldState};
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+
+/// node of a regressive (backward-chaining) search.
+/// Keeps the conditions that must be met before executing the rest of the plan.
+#[derive(Debug)]
+pub struct RegressiveNode<'a, T: PlanAction<U>, U> {
+    pub goal_state: WorldState,
+    extra_action_arguments: PhantomData<(&'a T, U)>,
+}
+
+impl<'a, T: PlanAction<U>, U> Clone for RegressiveNode<'a, T, U> {
+    fn clone(&self) -> Self {
+        RegressiveNode {
+            goal_state: self.goal_state.clone(),
+            extra_action_arguments: PhantomData,
+        }
+    }
+}
+
+impl<T: PlanAction<U>, U> Eq for RegressiveNode<'_, T, U> {}
+
+/// nodes are compared only by the conditions left to satisfy
+impl<T: PlanAction<U>, U> PartialEq<Self> for RegressiveNode<'_, T, U> {
+    fn eq(&self, other: &Self) -> bool {
+        self.goal_state.inner == other.goal_state.inner
+    }
+}
+
+impl<T: PlanAction<U>, U> Hash for RegressiveNode<'_, T, U> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.goal_state.hash(state);
+    }
+}
+
+impl<'a, T: PlanAction<U>, U> RegressiveNode<'a, T, U> {
+    pub fn initial(goal_state: &WorldState) -> RegressiveNode<'a, T, U> {
+        RegressiveNode {
+            goal_state: goal_state.clone(),
+            extra_action_arguments: PhantomData,
+        }
+    }
+
+    /// returns the conditions that must be met before executing given action,
+    /// or None if action doesn't achieve any of the conditions or conflicts with them.
+    fn regress(
+        &self,
+        action: &T,
+        action_arguments: &U,
+        initial_state: &WorldState,
+    ) -> Option<WorldState> {
+        let mut goal_state = self.goal_state.clone();
+        let mut is_relevant = false;
+        let effects = action.get_action_effects(action_arguments);
+        for (idx, effect) in effects.inner.iter().enumerate() {
+            let (Some(effect), Some(goal)) = (effect, self.goal_state.inner[idx].as_ref()) else {
+                continue;
+            };
+            if matches!(effect, WSProperty::Add(_) | WSProperty::Sub(_)) {
+                // arithmetic effect shifts the condition instead of satisfying it
+                goal_state.inner[idx] = Some(goal.regress(effect)?);
+                // only effects moving the initial value towards the condition are relevant,
+                // otherwise regression could go on forever
+                is_relevant |= goal.is_approached_by(effect, initial_state.inner[idx].as_ref());
+                continue;
+            }
+            // action would undo one of the conditions
+            if !goal.is_satisfied_by(Some(effect)) {
+                return None;
+            }
+            // condition might be already met by the initial state, but some other action
+            // required later in the plan can still break it
+            is_relevant = true;
+            goal_state.inner[idx] = None;
+        }
+
+        // effects don't satisfy any condition
+        if !is_relevant {
+            return None;
+        }
+
+        for (idx, precondition) in action.get_action_preconditions().inner.iter().enumerate() {
+            let Some(precondition) = precondition else {
+                continue;
+            };
+            goal_state.inner[idx] = match goal_state.inner[idx].as_ref() {
+                // None if precondition contradicts a condition required later in the plan
+                Some(goal) => Some(goal.intersect(precondition)?),
+                None => Some(precondition.clone()),
+            };
+        }
+        Some(goal_state)
+    }
+
+    pub fn neighbours(
+        &self,
+        actions: &'a [T],
+        action_arguments: &'a U,
+        initial_state: &WorldState,
+    ) -> Vec<(RegressiveNode<'a, T, U>, u32)> {
+        actions
+            .iter()
+            .filter_map(|action| {
+                let goal_state = self.regress(action, action_arguments, initial_state)?;
+
+                if !action.check_action_procedural_preconditions(action_arguments) {
+                    return None;
+                }
+
+                // binding doesn't change the effects, so the cheapest one is always the best
+                let (_binding, cost) = bind_action(action, action_arguments)?;
+
+                Some((
+                    RegressiveNode {
+                        goal_state,
+                        extra_action_arguments: PhantomData,
+                    },
+                    cost,
+                ))
+            })
+            .collect()
+    }
+
+    /// returns the cheapest (bound) action which regresses this node into the other one
+    fn cheapest_action_to(
+        &self,
+        other: &RegressiveNode<'a, T, U>,
+        actions: &'a [T],
+        action_arguments: &'a U,
+        initial_state: &WorldState,
+    ) -> Option<PlanStep<'a, T>> {
+        actions
+            .iter()
+            .filter(|action| {
+                self.regress(*action, action_arguments, initial_state)
+                    .map(|goal_state| goal_state.inner == other.goal_state.inner)
+                    .unwrap_or(false)
+                    && action.check_action_procedural_preconditions(action_arguments)
+            })
+            .filter_map(|action| {
+                let (binding, cost) = bind_action(action, action_arguments)?;
+                Some((PlanStep { action, binding }, cost))
+            })
+            .min_by_key(|(_step, cost)| *cost)
+            .map(|(step, _cost)| step)
+    }
+}
+
+/// backward-chaining planner – starts from the goal state and chains actions whose effects satisfy
+/// unmet conditions, until all the conditions are met by the initial state.
+/// Only actions relevant to the goal are expanded, which keeps the search small for large action sets.
+pub fn plan_regressive<'a, T: PlanAction<U>, U>(
+    initial_state: &'a WorldState,
+    goal_state: &'a WorldState,
+    actions: &'a [T],
+    action_arguments: &'a U,
+    budget: &PlanBudget,
+) -> PlanResult<'a, T> {
+    let start = RegressiveNode::initial(goal_state);
+    let search = astar_with_budget(
+        &start,
+        |node| node.neighbours(actions, action_arguments, initial_state),
+        |node| {
+            node.goal_state
+                .count_unsatisfied_world_state_props(initial_state)
+        },
+        |node| {
+            node.goal_state
+                .count_unsatisfied_world_state_props(initial_state)
+                == 0
+        },
+        budget,
+    );
+    // nodes are deduplicated by state, so actions are recovered from consecutive pairs of nodes.
+    // Search goes backwards – the last action is found first.
+    // Partial plan consists of the last steps of the plan, with some of its preconditions unmet.
+    PlanResult::from_search(search, |path| {
+        path.windows(2)
+            .rev()
+            .filter_map(|pair| {
+                pair[0].cheapest_action_to(&pair[1], actions, action_arguments, initial_state)
+            })
+            .collect()
+    })
+}
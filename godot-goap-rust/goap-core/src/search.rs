@@ -0,0 +1,172 @@
+use std::cmp::Ordering;
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap};
+use std::hash::Hash;
+use std::time::{Duration, Instant};
+
+/// limits of a single plan search. Default budget is unlimited.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PlanBudget {
+    /// max number of nodes expanded before giving up
+    pub max_nodes: Option<u32>,
+    /// max wall time spent on a search
+    pub max_time: Option<Duration>,
+}
+
+impl PlanBudget {
+    pub fn with_max_nodes(mut self, max_nodes: u32) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    pub fn with_max_time(mut self, max_time: Duration) -> Self {
+        self.max_time = Some(max_time);
+        self
+    }
+
+    fn is_exceeded(&self, nodes_expanded: u32, start_time: &Instant) -> bool {
+        self.max_nodes
+            .map(|max| nodes_expanded >= max)
+            .unwrap_or(false)
+            || self
+                .max_time
+                .map(|max| start_time.elapsed() >= max)
+                .unwrap_or(false)
+    }
+}
+
+#[derive(Debug)]
+pub(crate) enum SearchOutcome<N> {
+    Found(Vec<N>),
+    /// whole search space has been checked
+    Exhausted,
+    /// path to the node closest to the goal (by heuristic)
+    BudgetExceeded(Vec<N>),
+}
+
+/// orders open nodes by the smallest estimated cost, then by the biggest cost – same as `pathfinding::astar`
+struct SmallestCostHolder {
+    estimated_cost: u32,
+    cost: u32,
+    index: usize,
+}
+
+impl PartialEq for SmallestCostHolder {
+    fn eq(&self, other: &Self) -> bool {
+        self.estimated_cost == other.estimated_cost && self.cost == other.cost
+    }
+}
+
+impl Eq for SmallestCostHolder {}
+
+impl PartialOrd for SmallestCostHolder {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SmallestCostHolder {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match other.estimated_cost.cmp(&self.estimated_cost) {
+            Ordering::Equal => self.cost.cmp(&other.cost),
+            s => s,
+        }
+    }
+}
+
+/// A* search that stops after exceeding given budget.
+/// Visits nodes in the very same order as `pathfinding::astar`, so budget-less searches return the same plans.
+/// Returns an outcome and number of expanded nodes.
+pub(crate) fn astar_with_budget<N, FN, IN, FH, FS>(
+    start: &N,
+    mut successors: FN,
+    mut heuristic: FH,
+    mut success: FS,
+    budget: &PlanBudget,
+) -> (SearchOutcome<N>, u32)
+where
+    N: Eq + Hash + Clone,
+    FN: FnMut(&N) -> IN,
+    IN: IntoIterator<Item = (N, u32)>,
+    FH: FnMut(&N) -> u32,
+    FS: FnMut(&N) -> bool,
+{
+    let start_time = Instant::now();
+    let mut nodes_expanded: u32 = 0;
+    // (node, parent index, cost) – indexed in insertion order
+    let mut nodes: Vec<(N, usize, u32)> = vec![(start.clone(), usize::MAX, 0)];
+    let mut indexes: HashMap<N, usize> = HashMap::from([(start.clone(), 0)]);
+    // index of the expanded node with the smallest heuristic, used for a partial plan
+    let (mut closest, mut closest_heuristic) = (0, heuristic(start));
+    let mut to_see = BinaryHeap::new();
+    to_see.push(SmallestCostHolder {
+        estimated_cost: 0,
+        cost: 0,
+        index: 0,
+    });
+
+    while let Some(SmallestCostHolder { cost, index, .. }) = to_see.pop() {
+        let successors = {
+            let (node, _parent, node_cost) = &nodes[index];
+            if success(node) {
+                return (
+                    SearchOutcome::Found(reverse_path(&nodes, index)),
+                    nodes_expanded,
+                );
+            }
+            // node might have been pushed several times if a better way to access it has been found.
+            // Skip outdated entries.
+            if cost > *node_cost {
+                continue;
+            }
+            if budget.is_exceeded(nodes_expanded, &start_time) {
+                return (
+                    SearchOutcome::BudgetExceeded(reverse_path(&nodes, closest)),
+                    nodes_expanded,
+                );
+            }
+            nodes_expanded += 1;
+            let h = heuristic(node);
+            if h < closest_heuristic {
+                (closest, closest_heuristic) = (index, h);
+            }
+            successors(node)
+        };
+        for (successor, move_cost) in successors {
+            let new_cost = cost + move_cost;
+            let n = match indexes.entry(successor) {
+                Entry::Vacant(e) => {
+                    let n = nodes.len();
+                    nodes.push((e.key().clone(), index, new_cost));
+                    e.insert(n);
+                    n
+                }
+                Entry::Occupied(e) => {
+                    let n = *e.get();
+                    if nodes[n].2 <= new_cost {
+                        continue;
+                    }
+                    (nodes[n].1, nodes[n].2) = (index, new_cost);
+                    n
+                }
+            };
+            to_see.push(SmallestCostHolder {
+                estimated_cost: new_cost + heuristic(&nodes[n].0),
+                cost: new_cost,
+                index: n,
+            });
+        }
+    }
+    (SearchOutcome::Exhausted, nodes_expanded)
+}
+
+fn reverse_path<N: Clone>(nodes: &[(N, usize, u32)], mut index: usize) -> Vec<N> {
+    let mut path = Vec::new();
+    while index != usize::MAX {
+        let (node, parent, _cost) = &nodes[index];
+        path.push(node.clone());
+        index = *parent;
+    }
+    path.reverse();
+    path
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use std::ops::{Add, Sub};
+
+/// minimal 3D vector used by the AI core instead of an engine-specific one
+#[derive(Debug, Default, Copy, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Vec3 {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3::new(0.0, 0.0, 0.0);
+    const EPSILON: f32 = 0.00001;
+
+    pub const fn new(x: f32, y: f32, z: f32) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    pub fn length_squared(self) -> f32 {
+        self.x * self.x + self.y * self.y + self.z * self.z
+    }
+
+    pub fn length(self) -> f32 {
+        self.length_squared().sqrt()
+    }
+
+    pub fn distance_to(self, other: Vec3) -> f32 {
+        (other - self).length()
+    }
+
+    pub fn is_zero_approx(self) -> bool {
+        self.x.abs() < Self::EPSILON && self.y.abs() < Self::EPSILON && self.z.abs() < Self::EPSILON
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Vec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+/// opaque id of some entity (character, object etc.) existing in the world.
+/// In godot it maps 1:1 to an InstanceId.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct EntityId(pub i64);
+
+#[cfg(feature = "godot")]
+mod godot_conversions {
+    use super::{EntityId, Vec3};
+    use godot::prelude::{InstanceId, Vector3};
+
+    impl From<Vector3> for Vec3 {
+        fn from(v: Vector3) -> Self {
+            Vec3::new(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<Vec3> for Vector3 {
+        fn from(v: Vec3) -> Self {
+            Vector3::new(v.x, v.y, v.z)
+        }
+    }
+
+    impl From<InstanceId> for EntityId {
+        fn from(id: InstanceId) -> Self {
+            EntityId(id.to_i64())
+        }
+    }
+
+    impl From<EntityId> for InstanceId {
+        fn from(id: EntityId) -> Self {
+            InstanceId::from_i64(id.0)
+        }
+    }
+}
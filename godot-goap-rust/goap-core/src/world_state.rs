@@ -5,7 +5,6 @@ use std::fmt::{Debug, Formatter};
 use std::hash::{Hash, Hasher};
 use std::ops::{Index, IndexMut};
 
-use crate::targeting::target::TargetType;
 use serde::de::{MapAccess, Visitor};
 use serde::ser::SerializeMap;
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
@@ -55,6 +54,17 @@ pub enum DistanceToTarget {
     OutsideReach,
 }
 
+/// kind of the current target
+#[derive(Serialize, Deserialize, Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum TargetType {
+    Character,
+    CombatOpportunity,
+    Disturbance,
+    Interest,
+    Object,
+    SmartObject,
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Eq, PartialEq, Hash)]
 pub enum WSProperty {
     String(String),
@@ -64,6 +74,140 @@ pub enum WSProperty {
     CoverStatus(CoverStatusType),
     DistanceToTarget(DistanceToTarget),
     Target(TargetType),
+    /// numeric value. Unset numeric properties are treated as 0.
+    Number(i32),
+    /// numeric predicates, meant to be used in preconditions and goals: value >= n
+    AtLeast(i32),
+    /// value < n
+    LessThan(i32),
+    /// min <= value < max
+    InRange(i32, i32),
+    /// arithmetic effects, meant to be used in action effects: value += n
+    Add(i32),
+    /// value -= n
+    Sub(i32),
+}
+
+impl WSProperty {
+    /// returns range [min, max) of values accepted by a numeric property
+    fn numeric_range(&self) -> Option<(i64, i64)> {
+        match self {
+            WSProperty::Number(n) => Some((*n as i64, *n as i64 + 1)),
+            WSProperty::AtLeast(n) => Some((*n as i64, i64::MAX)),
+            WSProperty::LessThan(n) => Some((i64::MIN, *n as i64)),
+            WSProperty::InRange(min, max) => Some((*min as i64, *max as i64)),
+            _ => None,
+        }
+    }
+
+    /// returns the most specific property accepting values from a given range, or None if range is empty
+    fn from_numeric_range(min: i64, max: i64) -> Option<WSProperty> {
+        // bounds outside i32 are unbounded
+        let min = if min <= i32::MIN as i64 {
+            i64::MIN
+        } else {
+            min
+        };
+        let max = if max > i32::MAX as i64 { i64::MAX } else { max };
+        if min >= max {
+            return None;
+        }
+        let property = match (min, max) {
+            (i64::MIN, i64::MAX) => WSProperty::AtLeast(i32::MIN),
+            (min, i64::MAX) => WSProperty::AtLeast(min as i32),
+            (i64::MIN, max) => WSProperty::LessThan(max as i32),
+            (min, max) if max == min + 1 => WSProperty::Number(min as i32),
+            (min, max) => WSProperty::InRange(min as i32, max as i32),
+        };
+        Some(property)
+    }
+
+    fn is_arithmetic(&self) -> bool {
+        matches!(self, WSProperty::Add(_) | WSProperty::Sub(_))
+    }
+
+    /// numeric value of given property, if any
+    fn as_number(value: Option<&WSProperty>) -> Option<i32> {
+        match value {
+            None => Some(0),
+            Some(WSProperty::Number(n)) => Some(*n),
+            Some(_) => None,
+        }
+    }
+
+    /// checks if given value satisfies this property (treated as a condition)
+    pub fn is_satisfied_by(&self, value: Option<&WSProperty>) -> bool {
+        if let Some((min, max)) = self.numeric_range() {
+            return Self::as_number(value)
+                .map(|n| (min..max).contains(&(n as i64)))
+                .unwrap_or(false);
+        }
+        // arithmetic effects always change the state
+        if self.is_arithmetic() {
+            return false;
+        }
+        value == Some(self)
+    }
+
+    /// returns a value after applying this property (treated as an effect) to a given one
+    pub fn apply(&self, value: Option<&WSProperty>) -> WSProperty {
+        match self {
+            WSProperty::Add(n) => WSProperty::Number(
+                Self::as_number(value)
+                    .unwrap_or_default()
+                    .saturating_add(*n),
+            ),
+            WSProperty::Sub(n) => WSProperty::Number(
+                Self::as_number(value)
+                    .unwrap_or_default()
+                    .saturating_sub(*n),
+            ),
+            _ => self.clone(),
+        }
+    }
+
+    /// returns a condition that must be met before applying given effect,
+    /// so that this condition holds after applying it.
+    /// Returns None if the effect makes this condition impossible to meet or isn't an arithmetic one.
+    pub fn regress(&self, effect: &WSProperty) -> Option<WSProperty> {
+        let shift = match effect {
+            WSProperty::Add(n) => -(*n as i64),
+            WSProperty::Sub(n) => *n as i64,
+            // only arithmetic effects can be regressed
+            _ => return None,
+        };
+        let (min, max) = self.numeric_range()?;
+        let shift_bound = |bound: i64| {
+            if bound == i64::MIN || bound == i64::MAX {
+                return bound;
+            }
+            bound + shift
+        };
+        WSProperty::from_numeric_range(shift_bound(min), shift_bound(max))
+    }
+
+    /// checks if applying given arithmetic effect to an unsatisfying value brings it closer to this condition
+    pub fn is_approached_by(&self, effect: &WSProperty, value: Option<&WSProperty>) -> bool {
+        let (Some((min, max)), Some(value)) = (self.numeric_range(), Self::as_number(value)) else {
+            return false;
+        };
+        match effect {
+            WSProperty::Add(n) => *n > 0 && (value as i64) < min,
+            WSProperty::Sub(n) => *n > 0 && (value as i64) >= max,
+            _ => false,
+        }
+    }
+
+    /// returns a condition that meets both given conditions, or None if they contradict each other
+    pub fn intersect(&self, other: &WSProperty) -> Option<WSProperty> {
+        match (self.numeric_range(), other.numeric_range()) {
+            (Some((min, max)), Some((other_min, other_max))) => {
+                WSProperty::from_numeric_range(min.max(other_min), max.min(other_max))
+            }
+            _ if self == other => Some(self.clone()),
+            _ => None,
+        }
+    }
 }
 
 /// An abstraction that keeps symbolic representation of the world
@@ -93,6 +237,8 @@ pub enum WorldStateProperty {
     IsWeaponLoaded,
     IsRecoveringFromAttack,
     ReactedToWorldStateEvent,
+    /// distance to the current target, in meters
+    TargetDistance,
     Max,
 }
 
@@ -227,8 +373,8 @@ impl WorldState {
             if k == WorldStateProperty::Max {
                 break;
             }
-            if let Some(p) = other[k].clone() {
-                self[k] = Some(p);
+            if let Some(p) = other[k].as_ref() {
+                self[k] = Some(p.apply(self[k].as_ref()));
             }
         }
     }
@@ -241,11 +387,9 @@ impl WorldState {
                 break;
             }
             let (property, other_property) = (self[key].as_ref(), other[key].as_ref());
-            if let Some(other_val) = property {
-                if let Some(equality) = other_property.map(|v| v == other_val) {
-                    if equality {
-                        continue;
-                    }
+            if let (Some(val), Some(other_val)) = (property, other_property) {
+                if other_val.is_satisfied_by(Some(val)) || val.is_satisfied_by(Some(other_val)) {
+                    continue;
                 }
             }
             if property.is_some() || other_property.is_some() {
@@ -263,11 +407,7 @@ impl WorldState {
                 break;
             }
             if let Some(property) = self[key].as_ref() {
-                let are_props_equal = other[key]
-                    .as_ref()
-                    .map(|other_property| other_property == property)
-                    .unwrap_or(false);
-                if are_props_equal {
+                if property.is_satisfied_by(other[key].as_ref()) {
                     continue;
                 }
 
@@ -281,7 +421,7 @@ impl WorldState {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ai::world_state::WSProperty::Truth;
+    use crate::world_state::WSProperty::Truth;
     #[test]
     fn test_get_prop() {
         let state = WorldState::from([(WorldStateProperty::IsIdling, Truth(true))]);
@@ -323,4 +463,41 @@ mod tests {
         assert_eq!(3, state.count_state_differences(&other_state));
         assert_eq!(2, state.count_unsatisfied_world_state_props(&other_state));
     }
+
+    #[test]
+    fn test_numeric_predicates() {
+        use WSProperty::{AtLeast, InRange, LessThan, Number};
+        let goal_state = WorldState::from([(WorldStateProperty::TargetDistance, InRange(2, 5))]);
+        let state = |n| WorldState::from([(WorldStateProperty::TargetDistance, Number(n))]);
+        assert_eq!(goal_state.count_unsatisfied_world_state_props(&state(1)), 1);
+        assert_eq!(goal_state.count_unsatisfied_world_state_props(&state(2)), 0);
+        assert_eq!(goal_state.count_unsatisfied_world_state_props(&state(5)), 1);
+        assert_eq!(goal_state.count_state_differences(&state(4)), 0);
+        // unset numeric property is treated as 0
+        assert!(LessThan(1).is_satisfied_by(None));
+        assert!(!AtLeast(1).is_satisfied_by(None));
+        assert_eq!(AtLeast(2).intersect(&LessThan(3)), Some(Number(2)));
+        assert_eq!(AtLeast(3).intersect(&LessThan(3)), None);
+    }
+
+    #[test]
+    fn test_arithmetic_effects() {
+        use WSProperty::{Add, AtLeast, InRange, Number, Sub};
+        let mut state = WorldState::default();
+        state.apply_world_state(&WorldState::from([(
+            WorldStateProperty::TargetDistance,
+            Add(3),
+        )]));
+        assert_eq!(state[WorldStateProperty::TargetDistance], Some(Number(3)));
+        state.apply_world_state(&WorldState::from([(
+            WorldStateProperty::TargetDistance,
+            Sub(5),
+        )]));
+        assert_eq!(state[WorldStateProperty::TargetDistance], Some(Number(-2)));
+        // value must be 2 lower before adding 2
+        assert_eq!(AtLeast(5).regress(&Add(2)), Some(AtLeast(3)));
+        assert_eq!(InRange(0, 2).regress(&Sub(2)), Some(InRange(2, 4)));
+        assert!(AtLeast(5).is_approached_by(&Add(2), Some(&Number(1))));
+        assert!(!AtLeast(5).is_approached_by(&Sub(2), Some(&Number(1))));
+    }
 }
@@ -1,4 +1,11 @@
-#[derive(Debug, Default, Eq, PartialEq)]
+use crate::ai::types::EntityId;
+use godot::prelude::*;
+use serde::{Deserialize, Serialize};
+
+#[derive(
+    Debug, Default, Clone, Copy, Eq, PartialEq, GodotConvert, Var, Export, Serialize, Deserialize,
+)]
+#[godot(via = i32)]
 pub enum AIStimulusType {
     #[default]
     None,
@@ -22,3 +29,52 @@ pub enum AIStimulusType {
     DamageStun,
     CombatOpportunity,
 }
+
+impl AIStimulusType {
+    pub fn is_sound(&self) -> bool {
+        matches!(
+            self,
+            AIStimulusType::WeaponFireSound
+                | AIStimulusType::WeaponReloadSound
+                | AIStimulusType::WeaponImpactSound
+                | AIStimulusType::FootstepSound
+                | AIStimulusType::AlarmSound
+                | AIStimulusType::DisturbanceSound
+                | AIStimulusType::DeathSound
+                | AIStimulusType::PainSound
+        )
+    }
+
+    pub fn is_damage(&self) -> bool {
+        matches!(
+            self,
+            AIStimulusType::DamageBullet
+                | AIStimulusType::DamageExplode
+                | AIStimulusType::DamageMelee
+                | AIStimulusType::DamageStun
+        )
+    }
+}
+
+/// stimulus broadcast into the world, sensed by the thinkers within its radius
+#[derive(Debug, Clone)]
+pub struct AIStimulus {
+    pub stimulus_type: AIStimulusType,
+    pub position: Vector3,
+    pub radius: f32,
+    /// strength of the stimulus at its source, in range of 0-1
+    pub intensity: f32,
+    /// entity that caused the stimulus, if any
+    pub source: Option<EntityId>,
+}
+
+impl AIStimulus {
+    /// strength of the stimulus sensed at a given position – fades linearly with the distance
+    pub fn attenuated_intensity(&self, at: Vector3) -> f32 {
+        if self.radius <= 0.0 {
+            return 0.0;
+        }
+        let falloff = 1.0 - self.position.distance_to(at) / self.radius;
+        self.intensity * falloff.clamp(0.0, 1.0)
+    }
+}
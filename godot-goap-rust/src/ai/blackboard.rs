@@ -1,13 +1,18 @@
 // BlackBoard is used by AI subsystems to share their requests, intents, and results.
 
+use crate::ai::planner::ActionBinding;
+use crate::ai::squad::SquadRole;
+use crate::ai::types::EntityId;
+use crate::ai::working_memory::Task;
 use crate::animations::animation_data::AnimationType;
+use crate::goap_goals::utility::GoalScore;
 use crate::targeting::target::AITarget;
 use crate::targeting::targeting_systems::TargetMask;
 use crate::thinker_states::navigation_subsystem::RotationTarget;
 use crate::thinker_states::types::ThinkerState;
 use godot::prelude::*;
-use std::collections::VecDeque;
-use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
 
 #[derive(Default, Debug)]
 pub enum Awareness {
@@ -29,62 +34,106 @@ pub enum NavigationTarget {
     /// patrol point
     PatrolPoint(u32, Vector3),
     Character(InstanceId),
+    Position(Vector3),
 }
 
 #[derive(Debug)]
 pub struct Failed {
     pub index: usize,
-    pub time: SystemTime,
+    /// game time of the failure
+    pub time: f64,
 }
 
 impl Failed {
-    pub fn new(index: usize) -> Self {
-        Failed {
-            index,
-            time: SystemTime::now(),
-        }
+    pub fn new(index: usize, time: f64) -> Self {
+        Failed { index, time }
     }
 }
 
+/// index of an action in the thinker action set, with its parameter bound by the planner
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct PlannedAction {
+    pub index: usize,
+    pub binding: Option<ActionBinding>,
+    /// compound action given action is a step of
+    #[serde(default)]
+    pub parent: Option<usize>,
+}
+
 #[derive(Default, Debug)]
 pub struct Blackboard {
     /// id of ainode locked by this entity
     pub current_locked_node: Option<u32>,
     /// pointer to some new state for thinker (Goto/animate).
     pub new_state: Option<Box<dyn ThinkerState + Send>>,
-    pub current_plan_ids: VecDeque<usize>,
+    pub current_plan_ids: VecDeque<PlannedAction>,
     pub current_goal: Option<usize>,
     /// todo – move it to Working Memory instead?
     pub failed_goals: Vec<Failed>,
     pub thinker_position: Vector3,
     pub target: Option<AITarget>,
     pub distance_to_target: Option<f32>,
+    /// fraction of the hit points left, None if thinker can't be damaged
+    pub health: Option<f32>,
     pub valid_targets: TargetMask,
+    /// game time at which the thinker has lost track of given characters
+    pub lost_characters: HashMap<EntityId, f64>,
     pub navigation_target: Option<NavigationTarget>,
     pub animation_target: Option<AnimationType>,
     pub invalidate_target: bool,
     pub invalidate_plan: bool,
     pub invalidate_attack: bool,
     pub chosen_attack_idx: Option<usize>,
+    /// chosen attack is being performed and can't be changed
+    pub attack_in_progress: bool,
+    /// attacks that can be performed right now, candidates for the parameterised actions
+    pub attack_candidates: Vec<usize>,
     pub rotation_target: Option<RotationTarget>,
     pub walk_speed: SpeedMod,
     pub rotation_speed: SpeedMod,
     pub desired_velocity: Option<Vector3>,
     pub animation_completed: bool,
+    /// role assigned by the squad coordinator
+    pub squad_role: Option<SquadRole>,
+    /// task given by the squad
+    pub task: Option<Task>,
+    /// scores of the goals evaluated during the last goal selection
+    pub goal_scores: Vec<GoalScore>,
 }
 
 impl Blackboard {
     pub fn current_action(&self) -> Option<usize> {
-        self.current_plan_ids.front().copied()
+        self.current_plan_ids.front().map(|planned| planned.index)
+    }
+
+    pub fn current_binding(&self) -> Option<ActionBinding> {
+        self.current_plan_ids
+            .front()
+            .and_then(|planned| planned.binding)
     }
 
     pub fn next_action(&mut self) -> Option<usize> {
-        self.current_plan_ids.pop_front()
+        self.current_plan_ids
+            .pop_front()
+            .map(|planned| planned.index)
+    }
+
+    /// forgets goals that failed more than a second before given game time
+    pub fn validate_failed(&mut self, now: f64) {
+        self.failed_goals.retain(|fail| now - fail.time < 1.0);
     }
 
-    pub fn validate_failed(&mut self) {
-        self.failed_goals
-            .retain(|fail| fail.time.elapsed().unwrap().as_secs_f64() < 1.0);
+    /// forgets the current goal & plan – their indices are no longer valid once the AI data is reloaded
+    pub fn reset_plan(&mut self) {
+        self.new_state = None;
+        self.current_goal = None;
+        self.current_plan_ids.clear();
+        self.failed_goals.clear();
+        self.goal_scores.clear();
+        self.chosen_attack_idx = None;
+        self.attack_candidates.clear();
+        self.attack_in_progress = false;
+        self.invalidate_plan = true;
     }
 
     pub fn is_goal_failed(&self, goal: usize) -> bool {
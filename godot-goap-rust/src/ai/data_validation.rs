@@ -0,0 +1,348 @@
+// Cross-checks the data set of a thinker – actions, goals, animations and sensors – when it is loaded.
+// Problems are reported as warnings, so they can be fixed before they cause a panic or a silently idle AI.
+
+use crate::ai::working_memory::WorkingMemoryFactTypeKey;
+use crate::ai::world_state::{WorldState, WorldStateProperty};
+use crate::animations::animation_data::{AnimationType, AnimationsData};
+use crate::goap_actions::action_component::ActionComponent;
+use crate::goap_actions::action_types::{Action, ActionBehavior};
+use crate::goap_goals::goal_component::GoalComponent;
+use crate::goap_goals::goal_types::{GoalBehaviour, GoalType};
+use crate::goap_goals::utility::ConsiderationInput;
+use crate::sensors::sensor_types::{EventSensor, PollingSensor, SensorEvent, SensorPolling};
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use strum::IntoEnumIterator;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum DataWarning {
+    /// action or goal plays an animation absent from the animations file
+    MissingAnimation {
+        user: String,
+        animation: AnimationType,
+    },
+    /// goal desires a property no action produces
+    UnreachableGoal {
+        goal: String,
+        property: WorldStateProperty,
+    },
+    /// none of the action effects is desired by any goal or required by any action
+    UnneededAction { action: String },
+    /// none of the facts written by the sensor is read by the goals
+    UnreadSensor { sensor: String },
+    /// step of the compound action names no action
+    UnknownCompoundStep { action: String, step: String },
+    /// step of the compound action is a compound action itself
+    NestedCompound { action: String, step: String },
+}
+
+impl Display for DataWarning {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DataWarning::MissingAnimation { user, animation } => {
+                write!(
+                    f,
+                    "{} uses animation {:?} missing from the animations file",
+                    user, animation
+                )
+            }
+            DataWarning::UnreachableGoal { goal, property } => {
+                write!(
+                    f,
+                    "goal {} desires {:?} which no action produces",
+                    goal, property
+                )
+            }
+            DataWarning::UnneededAction { action } => {
+                write!(f, "effects of action {} are never needed", action)
+            }
+            DataWarning::UnreadSensor { sensor } => {
+                write!(f, "facts written by sensor {} are never read", sensor)
+            }
+            DataWarning::UnknownCompoundStep { action, step } => {
+                write!(
+                    f,
+                    "compound action {} has step {} which names no action",
+                    action, step
+                )
+            }
+            DataWarning::NestedCompound { action, step } => {
+                write!(
+                    f,
+                    "compound action {} has step {} which is a compound action",
+                    action, step
+                )
+            }
+        }
+    }
+}
+
+fn properties(state: &WorldState) -> impl Iterator<Item = WorldStateProperty> + '_ {
+    WorldStateProperty::iter()
+        .filter(|property| *property != WorldStateProperty::Max)
+        .filter(|property| state[*property].is_some())
+}
+
+pub fn validate_data_set(
+    actions: &[ActionComponent],
+    goals: &[GoalComponent],
+    animations: &AnimationsData,
+    sensors: &[PollingSensor],
+    event_sensors: &[EventSensor],
+) -> Vec<DataWarning> {
+    let mut warnings = Vec::new();
+
+    let is_animation_missing =
+        |animation: AnimationType| animations.fields[animation as usize].is_none();
+    for action in actions.iter() {
+        // actions like GoTo look up their animations by type, regardless of the one given in the data
+        let mut used = vec![action.animation];
+        for animation in action.action_type.required_animations() {
+            if !used.contains(animation) {
+                used.push(*animation);
+            }
+        }
+        for animation in used
+            .into_iter()
+            .filter(|animation| is_animation_missing(*animation))
+        {
+            warnings.push(DataWarning::MissingAnimation {
+                user: format!("action {}", action.name),
+                animation,
+            });
+        }
+    }
+    for goal in goals.iter() {
+        let GoalType::SatisfyDesireByPlayingAnimationGoal(inner) = &goal.goal_type else {
+            continue;
+        };
+        if is_animation_missing(inner.animation_type) {
+            warnings.push(DataWarning::MissingAnimation {
+                user: format!("goal {}", goal.name),
+                animation: inner.animation_type,
+            });
+        }
+    }
+
+    let produced: HashSet<WorldStateProperty> = actions
+        .iter()
+        .flat_map(|action| properties(&action.effects))
+        .collect();
+    for goal in goals.iter() {
+        for property in properties(&goal.desired_state) {
+            if !produced.contains(&property) {
+                warnings.push(DataWarning::UnreachableGoal {
+                    goal: goal.name.clone(),
+                    property,
+                });
+            }
+        }
+    }
+
+    for action in actions.iter() {
+        let Action::Compound(compound) = &action.action_type else {
+            continue;
+        };
+        for step in compound.steps.iter() {
+            match actions.iter().find(|other| other.name == *step) {
+                None => warnings.push(DataWarning::UnknownCompoundStep {
+                    action: action.name.clone(),
+                    step: step.clone(),
+                }),
+                Some(ActionComponent {
+                    action_type: Action::Compound(_),
+                    ..
+                }) => warnings.push(DataWarning::NestedCompound {
+                    action: action.name.clone(),
+                    step: step.clone(),
+                }),
+                Some(_) => {}
+            }
+        }
+    }
+
+    let needed: HashSet<WorldStateProperty> = goals
+        .iter()
+        .flat_map(|goal| properties(&goal.desired_state))
+        .chain(
+            actions
+                .iter()
+                .flat_map(|action| properties(&action.preconditions)),
+        )
+        .collect();
+    // steps of the compound actions are needed by them
+    let steps: HashSet<&String> = actions
+        .iter()
+        .filter_map(|action| match &action.action_type {
+            Action::Compound(compound) => Some(compound.steps.iter()),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+    for action in actions.iter() {
+        if steps.contains(&action.name) {
+            continue;
+        }
+        if !properties(&action.effects).any(|property| needed.contains(&property)) {
+            warnings.push(DataWarning::UnneededAction {
+                action: action.name.clone(),
+            });
+        }
+    }
+
+    // characters are always read by the target selection
+    let mut read: HashSet<WorkingMemoryFactTypeKey> =
+        HashSet::from([WorkingMemoryFactTypeKey::Knowledge]);
+    for goal in goals.iter() {
+        read.extend(goal.goal_type.read_facts());
+        read.extend(goal.considerations.iter().filter_map(
+            |consideration| match consideration.input {
+                ConsiderationInput::FactConfidence(fact_type) => Some(fact_type),
+                ConsiderationInput::TimeSinceTargetSeen => {
+                    Some(WorkingMemoryFactTypeKey::Knowledge)
+                }
+                _ => None,
+            },
+        ));
+    }
+    let written = sensors
+        .iter()
+        .map(|sensor| (<&str>::from(sensor), sensor.written_facts()))
+        .chain(
+            event_sensors
+                .iter()
+                .map(|sensor| (<&str>::from(sensor), sensor.written_facts())),
+        );
+    for (sensor, written) in written {
+        if !written.is_empty() && !written.iter().any(|fact_type| read.contains(fact_type)) {
+            warnings.push(DataWarning::UnreadSensor {
+                sensor: sensor.to_string(),
+            });
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::animations::animation_data::AnimationProps;
+
+    const ACTIONS: &str = r#"[
+        (name: "Goto", cost: 1, preconditions: {}, effects: {AtTargetPosition: Truth(true)}, animation: Walk, action_type: GoTo()),
+        (name: "Patrol", cost: 1, preconditions: {AtTargetPosition: Truth(true)}, effects: {IsAreaSurveyed: Truth(true)}, animation: Walk, action_type: Patrol()),
+        (name: "Idle", cost: 1, preconditions: {}, effects: {IsIdling: Truth(true)}, animation: Idle, action_type: Animate()),
+    ]"#;
+
+    const GOALS: &str = r#"[
+        (name: "Patrol", goal_type: PatrolGoal(), priority: 2, goto_target: Some(PatrolPoint), desired_state: {IsAreaSurveyed: Truth(true)}, required_state: {}),
+        (name: "KillEnemy", goal_type: BasicGoal(), priority: 5, goto_target: None, desired_state: {IsTargetDead: Truth(true)}, required_state: {}),
+    ]"#;
+
+    fn animations(types: &[AnimationType]) -> AnimationsData {
+        let mut animations = AnimationsData::default();
+        for animation in types {
+            animations.fields[*animation as usize] = Some(AnimationProps::default());
+        }
+        animations
+    }
+
+    #[test]
+    fn test_missing_animations() {
+        let actions: Vec<ActionComponent> = ron::from_str(ACTIONS).unwrap();
+        let warnings = validate_data_set(
+            &actions,
+            &[],
+            &animations(&[AnimationType::Walk, AnimationType::Idle]),
+            &[],
+            &[],
+        );
+        let missing: Vec<(&str, AnimationType)> = warnings
+            .iter()
+            .filter_map(|warning| match warning {
+                DataWarning::MissingAnimation { user, animation } => {
+                    Some((user.as_str(), *animation))
+                }
+                _ => None,
+            })
+            .collect();
+        // patrol action plays the patrol animation no matter which one is given in the data
+        assert_eq!(missing, vec![("action Patrol", AnimationType::Patrol)]);
+    }
+
+    #[test]
+    fn test_unreachable_goals_and_unneeded_actions() {
+        let actions: Vec<ActionComponent> = ron::from_str(ACTIONS).unwrap();
+        let goals: Vec<GoalComponent> = ron::from_str(GOALS).unwrap();
+        let warnings = validate_data_set(&actions, &goals, &AnimationsData::default(), &[], &[]);
+        assert!(warnings.contains(&DataWarning::UnreachableGoal {
+            goal: "KillEnemy".to_string(),
+            property: WorldStateProperty::IsTargetDead,
+        }));
+        assert!(warnings.contains(&DataWarning::UnneededAction {
+            action: "Idle".to_string(),
+        }));
+        // effects required by the other actions are needed as well
+        assert!(!warnings.iter().any(|warning| matches!(
+            warning,
+            DataWarning::UnreachableGoal { goal, .. } if goal == "Patrol"
+        ) || matches!(
+            warning,
+            DataWarning::UnneededAction { action } if action == "Goto" || action == "Patrol"
+        )));
+    }
+
+    #[test]
+    fn test_unread_sensors() {
+        let goals: Vec<GoalComponent> = ron::from_str(GOALS).unwrap();
+        let sensors: Vec<PollingSensor> = ron::from_str(
+            r#"[
+                SmartObjectSensor(update_every: 3.0, last_update_delta: 0.0),
+                TargetLostSensor(update_every: 0.5, last_update_delta: 0.0, lost_after: 8.0, remember_for: 60.0),
+            ]"#,
+        )
+        .unwrap();
+        let unread = |goals: &[GoalComponent]| -> Vec<DataWarning> {
+            validate_data_set(&[], goals, &AnimationsData::default(), &sensors, &[])
+                .into_iter()
+                .filter(|warning| matches!(warning, DataWarning::UnreadSensor { .. }))
+                .collect()
+        };
+        // characters are always read by the target selection
+        assert_eq!(
+            unread(&goals[1..]),
+            vec![DataWarning::UnreadSensor {
+                sensor: "SmartObjectSensor".to_string(),
+            }]
+        );
+        // nodes are read by the patrol goal
+        assert!(unread(&goals).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_compound_steps() {
+        let actions: Vec<ActionComponent> = ron::from_str(
+            r#"[
+                (name: "Arm", cost: 1, preconditions: {}, effects: {}, animation: AttackPrepare, action_type: ArmWeapon(), is_plannable: false),
+                (name: "Arm & fire", cost: 2, preconditions: {}, effects: {IsTargetDead: Truth(true)}, animation: Attack, action_type: Compound(steps: ["Arm", "Fire"])),
+                (name: "Arm twice", cost: 2, preconditions: {}, effects: {IsTargetDead: Truth(true)}, animation: Attack, action_type: Compound(steps: ["Arm", "Arm & fire"])),
+            ]"#,
+        )
+        .unwrap();
+        let warnings = validate_data_set(&actions, &[], &AnimationsData::default(), &[], &[]);
+        assert!(warnings.contains(&DataWarning::UnknownCompoundStep {
+            action: "Arm & fire".to_string(),
+            step: "Fire".to_string(),
+        }));
+        assert!(warnings.contains(&DataWarning::NestedCompound {
+            action: "Arm twice".to_string(),
+            step: "Arm & fire".to_string(),
+        }));
+        // valid steps aren't reported
+        assert!(!warnings.iter().any(|warning| matches!(
+            warning,
+            DataWarning::UnknownCompoundStep { step, .. } | DataWarning::NestedCompound { step, .. }
+                if step == "Arm"
+        )));
+    }
+}
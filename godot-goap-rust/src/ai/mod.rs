@@ -1,8 +1,9 @@
 pub mod ai_stimulus;
 pub mod blackboard;
-pub mod planner;
+pub mod data_validation;
 pub(crate) mod process_plan;
+pub mod replay;
+pub mod squad;
 pub mod thinker;
-pub mod working_memory;
 mod working_memory_query;
-pub mod world_state;
+pub use goap_core::{clock, plan_cache, planner, types, working_memory, world_state};
@@ -1,41 +1,51 @@
-use crate::ai::blackboard::{Blackboard, Failed};
-use crate::ai::planner::plan;
+use crate::ai::blackboard::{Blackboard, Failed, PlannedAction};
+use crate::ai::plan_cache::{to_cached_plan, CachedPlan, PlanCache, PlanCacheKey};
+use crate::ai::planner::{plan_with_budget, PlanAction, PlanBudget, PlanResult, SearchMode};
+use crate::ai::replay::{DecisionRecord, PlanRecorder, ThinkerSnapshot};
 use crate::ai::thinker::{Thinker, ThinkerShared};
-use crate::ai::working_memory::Event::GoalFailed;
-use crate::ai::working_memory::{FactQuery, FactQueryCheck, WMProperty, WorkingMemory};
+use crate::ai::working_memory::Event::{GoalFailed, PreconditionsViolated};
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, WMEventType, WMProperty, WorkingMemory,
+};
 use crate::ai::world_state::WorldState;
 use crate::ai_nodes::ai_node::AINode;
 use crate::animations::animation_data::AnimationsData;
+use crate::attacks::attack_data::AttackData;
 use crate::goap_actions::action_component::ActionComponent;
+use crate::goap_actions::action_parameter::apply_binding;
 use crate::goap_actions::action_types::ActionBehavior;
+use crate::goap_actions::compound_action::{expand_compound_action, expected_state_after_current};
 use crate::goap_goals::goal_component::GoalComponent;
 use crate::goap_goals::goal_types::GoalBehaviour;
 use crate::{action_arguments, action_plan_context, thinker_process_to_goal_view};
 use godot::builtin::Rid;
-use godot::global::godot_print;
+use godot::global::{godot_print, godot_warn};
 use godot::obj::InstanceId;
+use rayon::prelude::*;
+use rayon::{ThreadPool, ThreadPoolBuilder};
 use std::collections::{HashMap, VecDeque};
+use std::path::PathBuf;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{Arc, Mutex, RwLock};
 
 #[derive(Debug)]
 pub enum ThinkerPlanEvent {
-    Process(ThinkerProcess),
+    Process(PlanBatch),
+    /// starts recording decisions of all the thinkers into a given file
+    StartRecording(PathBuf),
+    StopRecording,
+    /// resets plans of given thinkers after their AI data has been reloaded.
+    /// Thinkers carry the old data their plans have been made with.
+    ResetPlans(Vec<ThinkerProcess>),
     Terminate,
 }
 
-impl ThinkerPlanEvent {
-    fn terminate(&self) -> bool {
-        matches!(self, ThinkerPlanEvent::Terminate)
-    }
-    fn process_view(self) -> ThinkerProcess {
-        match self {
-            ThinkerPlanEvent::Process(v) => v,
-            ThinkerPlanEvent::Terminate => {
-                panic!("aaa")
-            }
-        }
-    }
+/// all the thinkers processed in a given frame
+#[derive(Default, Debug)]
+pub struct PlanBatch {
+    pub thinkers: Vec<ThinkerProcess>,
+    /// max number of plan searches in a single frame. 0 means unlimited.
+    pub max_replans: u32,
 }
 
 /// all the information about our Thinker sent via channel
@@ -47,8 +57,12 @@ pub struct ThinkerProcess {
     pub actions: Arc<Vec<ActionComponent>>,
     pub goals: Arc<Vec<GoalComponent>>,
     pub animations: Arc<AnimationsData>,
+    pub attacks: Arc<Vec<AttackData>>,
+    pub search_mode: SearchMode,
+    pub plan_budget: PlanBudget,
     pub navigation_map_rid: Option<Rid>,
     pub ai_nodes: Option<Arc<RwLock<HashMap<u32, AINode>>>>,
+    pub plan_cache: Option<Arc<Mutex<PlanCache>>>,
 }
 
 pub struct ThinkerPlanView<'a> {
@@ -56,8 +70,12 @@ pub struct ThinkerPlanView<'a> {
     pub goals: &'a Arc<Vec<GoalComponent>>,
     pub actions: &'a Arc<Vec<ActionComponent>>,
     pub animations: &'a Arc<AnimationsData>,
+    pub attacks: &'a Arc<Vec<AttackData>>,
+    pub search_mode: SearchMode,
+    pub plan_budget: &'a PlanBudget,
     pub navigation_map_rid: &'a Option<Rid>,
     pub ai_nodes: &'a mut Option<Arc<RwLock<HashMap<u32, AINode>>>>,
+    pub plan_cache: &'a Option<Arc<Mutex<PlanCache>>>,
     pub blackboard: &'a mut Blackboard,
     pub working_memory: &'a mut WorkingMemory,
     pub world_state: &'a mut WorldState,
@@ -73,7 +91,11 @@ impl From<&Thinker> for ThinkerProcess {
             actions: value.actions.clone(),
             navigation_map_rid: value.navigation_map_rid,
             ai_nodes: None,
+            plan_cache: None,
             animations: value.animations.clone(),
+            attacks: value.attacks.clone(),
+            search_mode: value.search_mode,
+            plan_budget: value.plan_budget,
         }
     }
 }
@@ -83,12 +105,19 @@ impl ThinkerProcess {
         self.ai_nodes = Some(ainodes);
         self
     }
+
+    pub fn with_plan_cache(mut self, plan_cache: Arc<Mutex<PlanCache>>) -> Self {
+        self.plan_cache = Some(plan_cache);
+        self
+    }
 }
 
-fn get_relevant_goal(thinker: &mut ThinkerPlanView) -> Option<usize> {
-    thinker.blackboard.validate_failed();
+pub(crate) fn get_relevant_goal(thinker: &mut ThinkerPlanView) -> Option<usize> {
+    let now = thinker.working_memory.now();
+    thinker.blackboard.validate_failed(now);
     let current_goal: Option<usize> = thinker.blackboard.current_goal;
     let mut best_priority: u32 = 0;
+    let mut scores = Vec::new();
     let context = thinker_process_to_goal_view!(thinker);
 
     if let Some(current) = current_goal {
@@ -96,9 +125,9 @@ fn get_relevant_goal(thinker: &mut ThinkerPlanView) -> Option<usize> {
             .goal_type
             .is_valid(&thinker.goals[current], &context)
         {
-            best_priority = thinker.goals[current]
-                .goal_type
-                .calculate_goal_relevance(&thinker.goals[current], &context);
+            let score = thinker.goals[current].relevance(current, &context);
+            best_priority = score.relevance;
+            scores.push(score);
         }
     }
 
@@ -128,68 +157,176 @@ fn get_relevant_goal(thinker: &mut ThinkerPlanView) -> Option<usize> {
             continue;
         }
 
-        let priority: u32 = goal.goal_type.calculate_goal_relevance(goal, &context);
-        if priority > best_priority {
-            best_priority = priority;
+        let score = goal.relevance(id, &context);
+        if score.relevance > best_priority {
+            best_priority = score.relevance;
             best_goal = Some(id);
         }
+        scores.push(score);
     }
+    thinker.blackboard.goal_scores = scores;
     best_goal
 }
 
-/// recalculate the best goal and plan
-fn update_plan(thinker_view: &mut ThinkerPlanView) -> Option<(VecDeque<usize>, usize)> {
-    let current_goal: Option<usize> = None;
-
-    let new_goal = get_relevant_goal(thinker_view)?;
+/// returns a plan for given goal – either cached or found by the planner
+pub(crate) fn find_plan(thinker_view: &mut ThinkerPlanView, new_goal: usize) -> CachedPlan {
+    let initial_state = thinker_view.world_state.clone();
+    find_plan_from(thinker_view, new_goal, initial_state)
+}
 
-    // bail if no goal change. Replan if plan is empty.
-    if let Some(equality) = current_goal.map(|g| new_goal == g) {
-        if equality {
-            return None;
+/// returns a plan for given goal starting from a given state
+fn find_plan_from(
+    thinker_view: &mut ThinkerPlanView,
+    new_goal: usize,
+    initial_state: WorldState,
+) -> CachedPlan {
+    let action_arguments = action_plan_context!(thinker_view);
+    let goal_state = &thinker_view.goals[new_goal].desired_state;
+    let cache_key = PlanCacheKey::new(
+        thinker_view.actions,
+        new_goal,
+        thinker_view.search_mode,
+        goal_state,
+        &initial_state,
+        &action_arguments,
+    );
+    let cached_plan = thinker_view.plan_cache.as_ref().and_then(|cache| {
+        cache
+            .lock()
+            .expect("plan cache mutex failed!")
+            .get(&cache_key)
+    });
+    match cached_plan {
+        Some(plan) => plan,
+        None => {
+            // get a plan
+            let plan_result = plan_with_budget(
+                thinker_view.search_mode,
+                thinker_view.plan_budget,
+                &initial_state,
+                goal_state,
+                thinker_view.actions,
+                &action_arguments,
+            );
+            let (plan, is_cacheable) = match plan_result {
+                PlanResult::Found { plan, .. } => {
+                    (Some(to_cached_plan(thinker_view.actions, &plan)), true)
+                }
+                PlanResult::NoPlan { nodes_expanded } => {
+                    godot_print!(
+                        "thinker {}: no plan for goal {} ({} nodes expanded)",
+                        thinker_view.id,
+                        thinker_view.goals[new_goal].name,
+                        nodes_expanded
+                    );
+                    (None, true)
+                }
+                // outcome depends on the budget of a given thinker, so it isn't cached
+                PlanResult::BudgetExceeded {
+                    partial_plan,
+                    nodes_expanded,
+                } => {
+                    godot_warn!(
+                        "thinker {}: plan search for goal {} exceeded its budget after {} nodes; best partial plan: {:?}",
+                        thinker_view.id,
+                        thinker_view.goals[new_goal].name,
+                        nodes_expanded,
+                        partial_plan
+                            .iter()
+                            .map(|step| &step.action.name)
+                            .collect::<Vec<_>>()
+                    );
+                    (None, false)
+                }
+            };
+            if let (true, Some(cache)) = (is_cacheable, thinker_view.plan_cache.as_ref()) {
+                cache
+                    .lock()
+                    .expect("plan cache mutex failed!")
+                    .insert(cache_key, plan.clone());
+            }
+            plan
         }
     }
+}
 
-    // bail if goal can't be activated
-    if !activate_new_goal(thinker_view, new_goal) {
-        let property = WMProperty::Event(GoalFailed { id: new_goal });
-        thinker_view
-            .working_memory
-            .add_or_update(property, 1.0, 30.0);
-        thinker_view
-            .blackboard
-            .failed_goals
-            .push(Failed::new(new_goal));
+/// activates a found plan or marks the goal as failed
+fn apply_plan(thinker_view: &mut ThinkerPlanView, new_goal: usize, plan: CachedPlan) {
+    let Some(plan) = plan else {
+        // goal failed – couldn't find any plan to satisfy it
+        fail_goal(thinker_view, new_goal);
+        return;
+    };
+    let planned_actions: VecDeque<PlannedAction> = plan
+        .into_iter()
+        .map(|(index, binding)| PlannedAction {
+            index,
+            binding,
+            parent: None,
+        })
+        .collect();
+    godot_print!("new plan! {:?}", planned_actions);
+    activate_plan(thinker_view, planned_actions, new_goal);
+}
+
+/// re-checks the action following the current one against the live world state.
+/// On violation returns the current goal & the state expected after the current action
+/// from which the plan should be repaired.
+pub(crate) fn monitor_plan(thinker_view: &mut ThinkerPlanView) -> Option<(usize, WorldState)> {
+    if thinker_view.blackboard.invalidate_plan {
         return None;
     }
-
-    let initial_state = thinker_view.world_state.clone();
+    let goal = thinker_view.blackboard.current_goal?;
+    let plan = &thinker_view.blackboard.current_plan_ids;
+    let next = plan.get(1).copied()?;
+    let expected_state =
+        expected_state_after_current(thinker_view.actions, thinker_view.world_state, plan)?;
+    let next_action = &thinker_view.actions[next.index];
     let action_arguments = action_plan_context!(thinker_view);
-    // get a plan
-    let some_plan = plan(
-        &initial_state,
-        &thinker_view.goals[new_goal].desired_state,
-        thinker_view.actions,
-        &action_arguments,
-    );
-    if let Some(plan) = some_plan {
-        let indexes: VecDeque<usize> = plan
-            .iter()
-            .filter_map(|step| thinker_view.actions.iter().position(|a| a == *step))
-            .collect();
-        return Some((indexes, new_goal));
-    } else {
-        // goal failed – couldn't find any plan to satisfy it
-        let property = WMProperty::Event(GoalFailed { id: new_goal });
-        thinker_view
-            .working_memory
-            .add_or_update(property, 1.0, 30.0);
-        thinker_view
-            .blackboard
-            .failed_goals
-            .push(Failed::new(new_goal));
+    let is_valid = next_action
+        .preconditions
+        .count_unsatisfied_world_state_props(&expected_state)
+        == 0
+        && next_action.check_action_procedural_preconditions(&action_arguments);
+    if is_valid {
+        return None;
     }
-    None
+
+    let property = WMProperty::Event(PreconditionsViolated { action: next.index });
+    thinker_view
+        .working_memory
+        .add_or_update(property, 1.0, 10.0);
+    Some((goal, expected_state))
+}
+
+/// replaces the rest of the plan with the one found from the state expected after the current action
+/// and falls back to the goal reselection if there is no such plan.
+fn apply_repair(thinker_view: &mut ThinkerPlanView, repaired: CachedPlan) {
+    match repaired {
+        Some(repaired) => {
+            // plan isn't monitored between the steps of a compound action,
+            // so the current action is never followed by the remaining steps of its compound
+            let plan = &mut thinker_view.blackboard.current_plan_ids;
+            plan.truncate(1);
+            plan.extend(repaired.into_iter().map(|(index, binding)| PlannedAction {
+                index,
+                binding,
+                parent: None,
+            }));
+            godot_print!("plan repaired! {:?}", plan);
+        }
+        None => thinker_view.blackboard.invalidate_plan = true,
+    }
+}
+
+/// marks given goal as failed, so it won't be picked again for a while
+fn fail_goal(thinker_view: &mut ThinkerPlanView, goal: usize) {
+    let property = WMProperty::Event(GoalFailed { id: goal });
+    thinker_view
+        .working_memory
+        .add_or_update(property, 1.0, 30.0);
+    let failed = Failed::new(goal, thinker_view.working_memory.now());
+    thinker_view.blackboard.failed_goals.push(failed);
 }
 
 /// called when one of the action has been completed.
@@ -208,13 +345,14 @@ fn advance_plan(thinker_view: &mut ThinkerPlanView) {
             return;
         }
 
+        expand_compound_action(
+            thinker_view.actions,
+            &mut thinker_view.blackboard.current_plan_ids,
+        );
         let current_action: Option<usize> = thinker_view.blackboard.current_action();
         // execute the current action
         if let Some(index) = current_action {
-            thinker_view.actions[index].action_type.execute_action(
-                &thinker_view.actions[index],
-                action_arguments!(thinker_view),
-            );
+            execute_current_action(thinker_view, index);
             // advance the plan if action has been completed instantly
             let action_arguments = action_arguments!(thinker_view);
             if !thinker_view.actions[index]
@@ -261,18 +399,35 @@ fn activate_new_goal(thinker_view: &mut ThinkerPlanView, new_goal: usize) -> boo
         .activate(&thinker_view.goals[new_goal], &mut context)
 }
 
-/// finalizes previous plan and activates the new one
-fn activate_plan(thinker_view: &mut ThinkerPlanView, new_plan: VecDeque<usize>, new_goal: usize) {
-    let first_action: usize = new_plan[0];
+/// exposes the binding chosen by the planner and executes given action
+fn execute_current_action(thinker_view: &mut ThinkerPlanView, index: usize) {
+    if let Some(binding) = thinker_view.blackboard.current_binding() {
+        apply_binding(&binding, &mut action_arguments!(thinker_view));
+    }
+    thinker_view.actions[index].action_type.execute_action(
+        &thinker_view.actions[index],
+        action_arguments!(thinker_view),
+    );
+}
 
+/// finalizes previous plan and activates the new one
+fn activate_plan(
+    thinker_view: &mut ThinkerPlanView,
+    new_plan: VecDeque<PlannedAction>,
+    new_goal: usize,
+) {
     // update blackboard
     thinker_view.blackboard.current_goal = Some(new_goal);
     thinker_view.blackboard.current_plan_ids = new_plan;
+    expand_compound_action(
+        thinker_view.actions,
+        &mut thinker_view.blackboard.current_plan_ids,
+    );
+    let Some(first_action) = thinker_view.blackboard.current_action() else {
+        return;
+    };
 
-    let action_arguments = action_arguments!(thinker_view);
-    thinker_view.actions[first_action]
-        .action_type
-        .execute_action(&thinker_view.actions[first_action], action_arguments);
+    execute_current_action(thinker_view, first_action);
     let action_arguments = action_arguments!(thinker_view);
     // advance the plan if it was finished imminently
     if thinker_view.actions[first_action]
@@ -283,8 +438,11 @@ fn activate_plan(thinker_view: &mut ThinkerPlanView, new_plan: VecDeque<usize>,
     }
 }
 
-fn process_goal_and_plan(event: ThinkerPlanEvent) {
-    let mut thinker_process = event.process_view();
+/// locks the data shared by given thinker and passes its plan view to a given function
+pub(crate) fn with_plan_view<R>(
+    thinker_process: &mut ThinkerProcess,
+    f: impl FnOnce(&mut ThinkerPlanView) -> R,
+) -> R {
     let Ok(mut shared_lock) = thinker_process.shared.lock() else {
         panic!("couldn't open thinker shared lock!")
     };
@@ -300,17 +458,25 @@ fn process_goal_and_plan(event: ThinkerPlanEvent) {
         goals: &thinker_process.goals,
         actions: &thinker_process.actions,
         animations: &thinker_process.animations,
+        attacks: &thinker_process.attacks,
+        search_mode: thinker_process.search_mode,
+        plan_budget: &thinker_process.plan_budget,
         navigation_map_rid: &thinker_process.navigation_map_rid,
         ai_nodes: &mut thinker_process.ai_nodes,
+        plan_cache: &thinker_process.plan_cache,
         blackboard,
         world_state,
         working_memory,
     };
+    f(&mut thinker_process_view)
+}
+
+fn should_check_for_new_goal(thinker_process_view: &mut ThinkerPlanView) -> bool {
     let should_invalidate = thinker_process_view.blackboard.invalidate_plan;
     let current_goal = thinker_process_view.blackboard.current_goal;
     let current_action = thinker_process_view.blackboard.current_action();
     let action_arguments = action_arguments!(thinker_process_view);
-    let should_check_for_new_goal = should_invalidate
+    should_invalidate
         || (current_action
             .map(|index| {
                 thinker_process_view.actions[index]
@@ -320,37 +486,272 @@ fn process_goal_and_plan(event: ThinkerPlanEvent) {
             .unwrap_or(true)
             && current_goal
                 .map(|index| thinker_process_view.goals[index].is_interruptible)
-                .unwrap_or(true));
-
-    if should_check_for_new_goal {
-        if let Some((new_p, new_g)) = update_plan(&mut thinker_process_view) {
-            godot_print!("new plan! {:?}", new_p);
-            activate_plan(&mut thinker_process_view, new_p, new_g);
-        }
-        thinker_process_view.blackboard.invalidate_plan = false;
-    }
+                .unwrap_or(true))
+}
 
+/// advances the plan if current action has been completed
+fn process_current_action(thinker_process_view: &mut ThinkerPlanView) {
     let current_action: Option<usize> = thinker_process_view.blackboard.current_action();
 
     if let Some(action) = current_action {
         let action_arguments = action_arguments!(thinker_process_view);
-        if thinker_process.actions[action]
+        if thinker_process_view.actions[action]
             .action_type
             .is_action_complete(&action_arguments)
         {
-            advance_plan(&mut thinker_process_view);
+            advance_plan(thinker_process_view);
+        }
+    }
+}
+
+/// plan repair requested by the plan monitor of a thinker
+struct PlanRepair {
+    goal: usize,
+    /// state expected after the current action, the repaired plan starts from
+    expected_state: WorldState,
+    /// inputs of the plan monitor, for the recorder
+    inputs: Option<ThinkerSnapshot>,
+}
+
+/// processes all the thinkers from a given frame.
+/// Goals are selected, plans are checked and applied sequentially in thinker-id order,
+/// while the plan searches – the most expensive part – run in parallel.
+/// Inputs and outcomes of all the decisions are written to the recorder, if any.
+/// Returns id of the thinker that should be the first one to replan in the next frame.
+fn process_batch(
+    mut batch: PlanBatch,
+    first_to_replan: u32,
+    pool: &ThreadPool,
+    mut recorder: Option<&mut PlanRecorder>,
+) -> u32 {
+    batch.thinkers.sort_by_key(|thinker| thinker.id);
+    let thinkers_len = batch.thinkers.len();
+    let is_recording = recorder.is_some();
+    let max_replans = match batch.max_replans {
+        0 => usize::MAX,
+        max => max as usize,
+    };
+    let mut replans: usize = 0;
+    // first thinker that couldn't replan due to the limit
+    let mut postponed: Option<u32> = None;
+    let mut new_goals: Vec<Option<usize>> = vec![None; thinkers_len];
+    let mut repairs: Vec<Option<PlanRepair>> = (0..thinkers_len).map(|_| None).collect();
+    // inputs & outcomes of the goal selection, for the recorder
+    let mut goal_decisions: Vec<Option<(ThinkerSnapshot, Option<usize>)>> =
+        (0..thinkers_len).map(|_| None).collect();
+
+    // select & activate goals or check the current plans. Start with the thinker postponed
+    // in the previous frame, so limited replans and repairs are spread evenly among all the thinkers.
+    let start = batch
+        .thinkers
+        .iter()
+        .position(|thinker| thinker.id >= first_to_replan)
+        .unwrap_or(0);
+    for idx in (0..thinkers_len).map(|i| (start + i) % thinkers_len) {
+        let thinker = &mut batch.thinkers[idx];
+        let id = thinker.id;
+        let goal_decision = &mut goal_decisions[idx];
+        new_goals[idx] = with_plan_view(thinker, |thinker_view| {
+            if !should_check_for_new_goal(thinker_view) {
+                return None;
+            }
+            let inputs = is_recording.then(|| ThinkerSnapshot::capture(thinker_view));
+            let new_goal = get_relevant_goal(thinker_view);
+            if let Some(inputs) = inputs {
+                *goal_decision = Some((inputs, new_goal));
+            }
+            if new_goal.is_some() && replans >= max_replans {
+                postponed.get_or_insert(id);
+                return None;
+            }
+            thinker_view.blackboard.invalidate_plan = false;
+            let new_goal = new_goal?;
+            // bail if goal can't be activated
+            if !activate_new_goal(thinker_view, new_goal) {
+                fail_goal(thinker_view, new_goal);
+                return None;
+            }
+            replans += 1;
+            Some(new_goal)
+        });
+        if new_goals[idx].is_some() {
+            continue;
+        }
+        // keep following the current plan, repairing it if needed
+        repairs[idx] = with_plan_view(thinker, |thinker_view| {
+            let (goal, expected_state) = monitor_plan(thinker_view)?;
+            if replans >= max_replans {
+                postponed.get_or_insert(id);
+                return None;
+            }
+            replans += 1;
+            Some(PlanRepair {
+                goal,
+                expected_state,
+                inputs: is_recording.then(|| ThinkerSnapshot::capture(thinker_view)),
+            })
+        });
+    }
+
+    // search for plans in parallel
+    let plans: Vec<Option<(Option<ThinkerSnapshot>, CachedPlan)>> = pool.install(|| {
+        batch
+            .thinkers
+            .par_iter_mut()
+            .zip(new_goals.par_iter().zip(repairs.par_iter()))
+            .map(|(thinker, (new_goal, repair))| {
+                with_plan_view(thinker, |thinker_view| match (*new_goal, repair) {
+                    (Some(new_goal), _) => {
+                        let plan_inputs =
+                            is_recording.then(|| ThinkerSnapshot::capture(thinker_view));
+                        Some((plan_inputs, find_plan(thinker_view, new_goal)))
+                    }
+                    (None, Some(repair)) => {
+                        // plan inputs of a repair start from the state expected after the current action
+                        let plan_inputs = is_recording.then(|| ThinkerSnapshot {
+                            world_state: repair.expected_state.clone(),
+                            ..ThinkerSnapshot::capture(thinker_view)
+                        });
+                        let repaired = find_plan_from(
+                            thinker_view,
+                            repair.goal,
+                            repair.expected_state.clone(),
+                        );
+                        Some((plan_inputs, repaired))
+                    }
+                    (None, None) => None,
+                })
+            })
+            .collect()
+    });
+
+    // apply the results in thinker-id order
+    let mut records: Vec<DecisionRecord> = Vec::new();
+    for ((((thinker, new_goal), repair), plan), goal_decision) in batch
+        .thinkers
+        .iter_mut()
+        .zip(new_goals)
+        .zip(repairs)
+        .zip(plans)
+        .zip(goal_decisions)
+    {
+        let (plan_inputs, plan) = plan.unzip();
+        let plan_inputs = plan_inputs.flatten();
+        let is_repair = repair.is_some();
+        if let Some(recorder) = &recorder {
+            if let Some((inputs, selected_goal)) = goal_decision {
+                records.push(DecisionRecord {
+                    tick: recorder.tick,
+                    thinker_id: thinker.id,
+                    inputs,
+                    selected_goal,
+                    plan_inputs: new_goal.and(plan_inputs.clone()),
+                    plan: new_goal.and(plan.clone()),
+                    is_repair: false,
+                });
+            }
+            if let Some(PlanRepair {
+                goal,
+                inputs: Some(inputs),
+                ..
+            }) = repair
+            {
+                records.push(DecisionRecord {
+                    tick: recorder.tick,
+                    thinker_id: thinker.id,
+                    inputs,
+                    selected_goal: Some(goal),
+                    plan_inputs,
+                    plan: plan.clone(),
+                    is_repair: true,
+                });
+            }
         }
+        with_plan_view(thinker, |thinker_view| {
+            match (new_goal, plan) {
+                (Some(new_goal), Some(plan)) => apply_plan(thinker_view, new_goal, plan),
+                (None, Some(repaired)) if is_repair => apply_repair(thinker_view, repaired),
+                _ => {}
+            }
+            process_current_action(thinker_view);
+        });
+    }
+
+    if let Some(recorder) = recorder.as_mut() {
+        for record in records.iter() {
+            if let Err(e) = recorder.write(record) {
+                godot_warn!("couldn't record AI decision: {}", e);
+            }
+        }
+        recorder.tick += 1;
+    }
+    postponed.unwrap_or(0)
+}
+
+/// abandons the current plan of a thinker – finishes its action & deactivates its goal, so they release
+/// locked nodes, navigation targets etc. – and drops it together with the facts referring to goals & actions
+/// by their indices. The rest of the working memory is kept.
+pub(crate) fn reset_plan(thinker_view: &mut ThinkerPlanView) {
+    if let Some(index) = thinker_view.blackboard.current_action() {
+        thinker_view.actions[index]
+            .action_type
+            .finish(action_arguments!(thinker_view));
+    }
+    if let Some(goal) = thinker_view.blackboard.current_goal {
+        let mut context = thinker_process_to_goal_view!(thinker_view);
+        thinker_view.goals[goal]
+            .goal_type
+            .deactivate(&thinker_view.goals[goal], &mut context);
+    }
+    thinker_view.blackboard.reset_plan();
+    for event_type in [WMEventType::GoalFailed, WMEventType::PreconditionsViolated] {
+        let query = FactQuery::with_check(FactQueryCheck::Event(event_type));
+        while thinker_view
+            .working_memory
+            .find_and_mark_as_invalid(query.clone())
+            .is_some()
+        {}
     }
 }
 
 pub fn process_plan(receiver: Receiver<ThinkerPlanEvent>, _sender: Sender<()>) {
-    loop {
-        if let Ok(message) = receiver.recv() {
-            if message.terminate() {
+    let pool = ThreadPoolBuilder::new()
+        .thread_name(|idx| format!("planner-{}", idx))
+        .build()
+        .expect("couldn't create planner thread pool!");
+    let mut first_to_replan: u32 = 0;
+    let mut recorder: Option<PlanRecorder> = None;
+    while let Ok(message) = receiver.recv() {
+        match message {
+            ThinkerPlanEvent::Process(batch) => {
+                first_to_replan = process_batch(batch, first_to_replan, &pool, recorder.as_mut());
+            }
+            ThinkerPlanEvent::StartRecording(path) => match PlanRecorder::create(&path) {
+                Ok(new_recorder) => {
+                    godot_print!("recording AI decisions to {}", path.display());
+                    recorder = Some(new_recorder);
+                }
+                Err(e) => godot_warn!("couldn't start recording to {}: {}", path.display(), e),
+            },
+            ThinkerPlanEvent::StopRecording => {
+                if let Some(Err(e)) = recorder.take().map(|mut r| r.flush()) {
+                    godot_warn!("couldn't finish recording: {}", e);
+                }
+            }
+            // processed in order, so plans found for the old data in previous batches are dropped as well
+            ThinkerPlanEvent::ResetPlans(mut thinkers) => {
+                for thinker in thinkers.iter_mut() {
+                    with_plan_view(thinker, reset_plan);
+                    // previous batches might have cached plans for the old action sets again
+                    if let Some(plan_cache) = thinker.plan_cache.as_ref() {
+                        plan_cache.lock().expect("plan cache mutex failed!").clear();
+                    }
+                }
+            }
+            ThinkerPlanEvent::Terminate => {
                 godot_print!("terminating…");
                 break;
             }
-            process_goal_and_plan(message)
         }
     }
 }
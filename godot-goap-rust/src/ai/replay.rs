@@ -0,0 +1,591 @@
+// Records inputs & outcomes of the AI decisions, so they can be replayed and compared later.
+// Recording consists of RON records, one per line – each describes a single decision made by a given thinker.
+
+use crate::ai::blackboard::{Blackboard, Failed, NavigationTarget, PlannedAction};
+use crate::ai::plan_cache::CachedPlan;
+use crate::ai::process_plan::{
+    find_plan, get_relevant_goal, monitor_plan, with_plan_view, ThinkerPlanView, ThinkerProcess,
+};
+use crate::ai::squad::SquadRole;
+use crate::ai::thinker::{Thinker, ThinkerShared};
+use crate::ai::types::{EntityId, Vec3};
+use crate::ai::working_memory::{
+    FactPolicy, FactSnapshot, Task, WMProperty, WorkingMemory, WorkingMemoryFactTypeKey,
+};
+use crate::ai::world_state::WorldState;
+use crate::ai_nodes::ai_node::{AINode, AINodeBase, CoverCone, SmartObjectInteraction};
+use crate::targeting::target::AITarget;
+use godot::obj::InstanceId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TargetSnapshot {
+    Character(EntityId, Option<Vec3>),
+    CombatOpportunity,
+    Disturbance(Vec3),
+    Interest(Vec3),
+    Object(EntityId),
+    SmartObject(EntityId),
+}
+
+impl From<&AITarget> for TargetSnapshot {
+    fn from(target: &AITarget) -> Self {
+        match target {
+            AITarget::Character(id, pos) => {
+                TargetSnapshot::Character((*id).into(), pos.map(Into::into))
+            }
+            AITarget::CombatOpportunity => TargetSnapshot::CombatOpportunity,
+            AITarget::Disturbance(pos) => TargetSnapshot::Disturbance((*pos).into()),
+            AITarget::Interest(pos) => TargetSnapshot::Interest((*pos).into()),
+            AITarget::Object(id) => TargetSnapshot::Object((*id).into()),
+            AITarget::SmartObject(id) => TargetSnapshot::SmartObject((*id).into()),
+        }
+    }
+}
+
+impl From<&TargetSnapshot> for AITarget {
+    fn from(target: &TargetSnapshot) -> Self {
+        match target {
+            TargetSnapshot::Character(id, pos) => {
+                AITarget::Character(InstanceId::from(*id), pos.map(Into::into))
+            }
+            TargetSnapshot::CombatOpportunity => AITarget::CombatOpportunity,
+            TargetSnapshot::Disturbance(pos) => AITarget::Disturbance((*pos).into()),
+            TargetSnapshot::Interest(pos) => AITarget::Interest((*pos).into()),
+            TargetSnapshot::Object(id) => AITarget::Object((*id).into()),
+            TargetSnapshot::SmartObject(id) => AITarget::SmartObject((*id).into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum NavigationTargetSnapshot {
+    PatrolPoint(u32, Vec3),
+    Character(EntityId),
+    Position(Vec3),
+}
+
+impl From<&NavigationTarget> for NavigationTargetSnapshot {
+    fn from(target: &NavigationTarget) -> Self {
+        match target {
+            NavigationTarget::PatrolPoint(id, pos) => {
+                NavigationTargetSnapshot::PatrolPoint(*id, (*pos).into())
+            }
+            NavigationTarget::Character(id) => NavigationTargetSnapshot::Character((*id).into()),
+            NavigationTarget::Position(pos) => NavigationTargetSnapshot::Position((*pos).into()),
+        }
+    }
+}
+
+impl From<&NavigationTargetSnapshot> for NavigationTarget {
+    fn from(target: &NavigationTargetSnapshot) -> Self {
+        match target {
+            NavigationTargetSnapshot::PatrolPoint(id, pos) => {
+                NavigationTarget::PatrolPoint(*id, (*pos).into())
+            }
+            NavigationTargetSnapshot::Character(id) => NavigationTarget::Character((*id).into()),
+            NavigationTargetSnapshot::Position(pos) => NavigationTarget::Position((*pos).into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum AINodeKindSnapshot {
+    Patrol {
+        next: Option<u32>,
+        orientation: Option<Vec3>,
+    },
+    Cover {
+        direction: Vec3,
+        half_angle: f32,
+    },
+    Hide,
+    Ambush {
+        direction: Vec3,
+        half_angle: f32,
+    },
+    SmartObject {
+        animation: String,
+        duration: f64,
+        object: Option<EntityId>,
+    },
+}
+
+/// ainode known to the thinker, with the lock state it had at the time of the decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AINodeSnapshot {
+    pub ainode_id: u32,
+    pub base_id: EntityId,
+    pub position: Vec3,
+    /// id of the thinker holding the node, 0 if the node is free
+    pub locked_by: u32,
+    pub kind: AINodeKindSnapshot,
+}
+
+impl AINodeSnapshot {
+    fn capture(ainode: &AINode) -> Option<Self> {
+        let kind = match ainode {
+            AINode::None => return None,
+            AINode::Patrol {
+                next, orientation, ..
+            } => AINodeKindSnapshot::Patrol {
+                next: *next,
+                orientation: orientation.map(Into::into),
+            },
+            AINode::Cover { cone, .. } => AINodeKindSnapshot::Cover {
+                direction: cone.direction.into(),
+                half_angle: cone.half_angle,
+            },
+            AINode::Hide { .. } => AINodeKindSnapshot::Hide,
+            AINode::Ambush { cone, .. } => AINodeKindSnapshot::Ambush {
+                direction: cone.direction.into(),
+                half_angle: cone.half_angle,
+            },
+            AINode::SmartObject { interaction, .. } => AINodeKindSnapshot::SmartObject {
+                animation: interaction.animation.clone(),
+                duration: interaction.duration,
+                object: interaction.object.map(Into::into),
+            },
+        };
+        let base = ainode.base();
+        Some(AINodeSnapshot {
+            ainode_id: base.ainode_id,
+            base_id: base.base_id.into(),
+            position: base.position.into(),
+            locked_by: base.status.load(Ordering::Acquire),
+            kind,
+        })
+    }
+
+    fn restore(&self) -> AINode {
+        let base = AINodeBase {
+            ainode_id: self.ainode_id,
+            base_id: self.base_id.into(),
+            position: self.position.into(),
+            status: AtomicU32::new(self.locked_by),
+        };
+        match &self.kind {
+            AINodeKindSnapshot::Patrol { next, orientation } => AINode::Patrol {
+                base,
+                next: *next,
+                orientation: orientation.map(Into::into),
+            },
+            AINodeKindSnapshot::Cover {
+                direction,
+                half_angle,
+            } => AINode::Cover {
+                base,
+                cone: CoverCone {
+                    direction: (*direction).into(),
+                    half_angle: *half_angle,
+                },
+            },
+            AINodeKindSnapshot::Hide => AINode::Hide { base },
+            AINodeKindSnapshot::Ambush {
+                direction,
+                half_angle,
+            } => AINode::Ambush {
+                base,
+                cone: CoverCone {
+                    direction: (*direction).into(),
+                    half_angle: *half_angle,
+                },
+            },
+            AINodeKindSnapshot::SmartObject {
+                animation,
+                duration,
+                object,
+            } => AINode::SmartObject {
+                base,
+                interaction: SmartObjectInteraction {
+                    animation: animation.clone(),
+                    duration: *duration,
+                    object: object.map(Into::into),
+                },
+            },
+        }
+    }
+}
+
+/// snapshots of the nodes the thinker knows about or holds – the ones its goals & actions might look up
+fn capture_ai_nodes(thinker_view: &ThinkerPlanView) -> Vec<AINodeSnapshot> {
+    let Some(ai_nodes) = thinker_view.ai_nodes.as_ref() else {
+        return Vec::new();
+    };
+    let Ok(ai_nodes) = ai_nodes.read() else {
+        panic!("rwlock failed – couldn't capture ainodes!")
+    };
+    let mut ids: Vec<u32> = thinker_view
+        .working_memory
+        .facts_of_type(WorkingMemoryFactTypeKey::Node)
+        .filter_map(|fact| match &fact.f_type {
+            WMProperty::Node(node) => Some(node.ainode_id()),
+            _ => None,
+        })
+        .chain(thinker_view.blackboard.current_locked_node)
+        .collect();
+    if let Some(NavigationTarget::PatrolPoint(id, _)) = thinker_view.blackboard.navigation_target {
+        ids.push(id);
+    }
+    ids.sort_unstable();
+    ids.dedup();
+    ids.iter()
+        .filter_map(|id| ai_nodes.get(id))
+        .filter_map(AINodeSnapshot::capture)
+        .collect()
+}
+
+/// part of the blackboard read by the goals & actions while making decisions
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlackboardSnapshot {
+    pub current_goal: Option<usize>,
+    pub current_plan: Vec<PlannedAction>,
+    /// goals that failed recently
+    pub failed_goals: Vec<usize>,
+    pub thinker_position: Vec3,
+    pub target: Option<TargetSnapshot>,
+    pub distance_to_target: Option<f32>,
+    pub health: Option<f32>,
+    pub squad_role: Option<SquadRole>,
+    #[serde(default)]
+    pub task: Option<Task>,
+    pub navigation_target: Option<NavigationTargetSnapshot>,
+    pub current_locked_node: Option<u32>,
+    pub chosen_attack_idx: Option<usize>,
+    pub attack_candidates: Vec<usize>,
+    pub invalidate_plan: bool,
+}
+
+impl From<&Blackboard> for BlackboardSnapshot {
+    fn from(blackboard: &Blackboard) -> Self {
+        BlackboardSnapshot {
+            current_goal: blackboard.current_goal,
+            current_plan: blackboard.current_plan_ids.iter().copied().collect(),
+            failed_goals: blackboard.failed_goals.iter().map(|f| f.index).collect(),
+            thinker_position: blackboard.thinker_position.into(),
+            target: blackboard.target.as_ref().map(Into::into),
+            distance_to_target: blackboard.distance_to_target,
+            health: blackboard.health,
+            squad_role: blackboard.squad_role,
+            task: blackboard.task.clone(),
+            navigation_target: blackboard.navigation_target.as_ref().map(Into::into),
+            current_locked_node: blackboard.current_locked_node,
+            chosen_attack_idx: blackboard.chosen_attack_idx,
+            attack_candidates: blackboard.attack_candidates.clone(),
+            invalidate_plan: blackboard.invalidate_plan,
+        }
+    }
+}
+
+impl BlackboardSnapshot {
+    fn restore(&self, now: f64) -> Blackboard {
+        Blackboard {
+            current_goal: self.current_goal,
+            current_plan_ids: self.current_plan.iter().copied().collect(),
+            // failure timers are restarted – snapshot keeps only the goals that haven't expired yet
+            failed_goals: self
+                .failed_goals
+                .iter()
+                .map(|idx| Failed::new(*idx, now))
+                .collect(),
+            thinker_position: self.thinker_position.into(),
+            target: self.target.as_ref().map(Into::into),
+            distance_to_target: self.distance_to_target,
+            health: self.health,
+            squad_role: self.squad_role,
+            task: self.task.clone(),
+            navigation_target: self.navigation_target.as_ref().map(Into::into),
+            current_locked_node: self.current_locked_node,
+            chosen_attack_idx: self.chosen_attack_idx,
+            attack_candidates: self.attack_candidates.clone(),
+            invalidate_plan: self.invalidate_plan,
+            ..Default::default()
+        }
+    }
+}
+
+/// everything a thinker knows at the time of making a decision
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ThinkerSnapshot {
+    pub world_state: WorldState,
+    pub facts: Vec<FactSnapshot>,
+    pub blackboard: BlackboardSnapshot,
+    #[serde(default)]
+    pub fact_policies: HashMap<WorkingMemoryFactTypeKey, FactPolicy>,
+    /// replay uses these instead of the live nodes, so it neither depends on them nor locks them
+    #[serde(default)]
+    pub ai_nodes: Vec<AINodeSnapshot>,
+}
+
+impl ThinkerSnapshot {
+    pub fn capture(thinker_view: &mut ThinkerPlanView) -> Self {
+        // drop expired failures, so the replay doesn't depend on the time of the recording
+        let now = thinker_view.working_memory.now();
+        thinker_view.blackboard.validate_failed(now);
+        ThinkerSnapshot {
+            world_state: thinker_view.world_state.clone(),
+            facts: thinker_view.working_memory.snapshot(),
+            blackboard: BlackboardSnapshot::from(&*thinker_view.blackboard),
+            fact_policies: thinker_view.working_memory.policies(),
+            ai_nodes: capture_ai_nodes(thinker_view),
+        }
+    }
+
+    fn restore(&self) -> ThinkerShared {
+        let working_memory =
+            WorkingMemory::from_snapshot(&self.facts).with_policies(&self.fact_policies);
+        ThinkerShared {
+            blackboard: self.blackboard.restore(working_memory.now()),
+            working_memory,
+            world_state: self.world_state.clone(),
+            target_mask: Default::default(),
+        }
+    }
+
+    fn restore_ai_nodes(&self) -> Arc<RwLock<HashMap<u32, AINode>>> {
+        let ai_nodes = self
+            .ai_nodes
+            .iter()
+            .map(|ainode| (ainode.ainode_id, ainode.restore()))
+            .collect();
+        Arc::new(RwLock::new(ai_nodes))
+    }
+}
+
+/// a single decision made by the thinker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DecisionRecord {
+    pub tick: u64,
+    pub thinker_id: u32,
+    /// inputs of the goal selection or the plan monitor
+    pub inputs: ThinkerSnapshot,
+    pub selected_goal: Option<usize>,
+    /// inputs of the plan search, taken after the goal has been activated. None if no search has been made.
+    pub plan_inputs: Option<ThinkerSnapshot>,
+    pub plan: Option<CachedPlan>,
+    /// plan has been repaired by the plan monitor for the current goal instead of a newly selected one
+    #[serde(default)]
+    pub is_repair: bool,
+}
+
+pub struct PlanRecorder {
+    writer: BufWriter<File>,
+    pub tick: u64,
+}
+
+impl PlanRecorder {
+    pub fn create(path: &Path) -> std::io::Result<Self> {
+        Ok(PlanRecorder {
+            writer: BufWriter::new(File::create(path)?),
+            tick: 0,
+        })
+    }
+
+    pub fn write(&mut self, record: &DecisionRecord) -> std::io::Result<()> {
+        let line = ron::to_string(record)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        writeln!(self.writer, "{}", line)
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+}
+
+/// feeds recorded inputs through the goal selection & the planner using given thinker.
+/// Returns descriptions of all the decisions that differ from the recorded ones.
+fn replay_decision(record: &DecisionRecord, thinker: &Thinker) -> Vec<String> {
+    let mut mismatches = Vec::new();
+    let process = |snapshot: &ThinkerSnapshot| ThinkerProcess {
+        shared: Arc::new(Mutex::new(snapshot.restore())),
+        ..ThinkerProcess::from(thinker).with_ainodes(snapshot.restore_ai_nodes())
+    };
+
+    if record.is_repair {
+        let repair = with_plan_view(&mut process(&record.inputs), monitor_plan);
+        if repair.is_none() {
+            mismatches.push(format!(
+                "tick {}, thinker {}: plan is valid, recorded repair",
+                record.tick, record.thinker_id
+            ));
+        }
+    } else {
+        let selected_goal = with_plan_view(&mut process(&record.inputs), get_relevant_goal);
+        if selected_goal != record.selected_goal {
+            mismatches.push(format!(
+                "tick {}, thinker {}: selected goal {:?}, recorded {:?}",
+                record.tick, record.thinker_id, selected_goal, record.selected_goal
+            ));
+        }
+    }
+
+    let (Some(plan_inputs), Some(recorded_plan), Some(goal)) = (
+        record.plan_inputs.as_ref(),
+        record.plan.as_ref(),
+        record.selected_goal,
+    ) else {
+        return mismatches;
+    };
+    // replayed without the plan cache
+    let plan = with_plan_view(&mut process(plan_inputs), |thinker_view| {
+        find_plan(thinker_view, goal)
+    });
+    if plan != *recorded_plan {
+        mismatches.push(format!(
+            "tick {}, thinker {}: plan {:?}, recorded {:?}",
+            record.tick, record.thinker_id, plan, recorded_plan
+        ));
+    }
+    mismatches
+}
+
+/// replays all the decisions from a given recording.
+/// Returns number of replayed decisions and descriptions of the ones that differ from the recorded ones.
+pub fn replay(path: &Path, thinkers: &HashMap<u32, Thinker>) -> Result<(u32, Vec<String>), String> {
+    let file = File::open(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let mut replayed: u32 = 0;
+    let mut mismatches = Vec::new();
+    for (line_number, line) in BufReader::new(file).lines().enumerate() {
+        let line = line.map_err(|e| format!("{}: {}", path.display(), e))?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let record: DecisionRecord = ron::from_str(&line)
+            .map_err(|e| format!("{}:{}: {}", path.display(), line_number + 1, e))?;
+        let Some(thinker) = thinkers.get(&record.thinker_id) else {
+            mismatches.push(format!(
+                "tick {}: no thinker with id {}",
+                record.tick, record.thinker_id
+            ));
+            continue;
+        };
+        mismatches.extend(replay_decision(&record, thinker));
+        replayed += 1;
+    }
+    Ok((replayed, mismatches))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ai::clock::GameClock;
+    use crate::ai::working_memory::{Knowledge, WMProperty};
+    use crate::goap_goals::goal_component::GoalComponent;
+    use crate::goap_goals::goal_types::{AgentGoalWorldContext, GoalBehaviour};
+    use godot::builtin::Vector3;
+
+    const CHASE_ENEMY: &str = r#"GoalComponent(
+        name: "ChaseEnemy",
+        goal_type: ChaseEnemyGoal(),
+        priority: 5,
+        goto_target: None,
+        desired_state: {AtTargetPosition: Truth(true)},
+        required_state: {HasTarget: Target(Character)},
+        considerations: [
+            (input: TimeSinceTargetSeen, range: (0.0, 60.0), curve: Linear(slope: -1.0, offset: 1.0)),
+            (input: DistanceToTarget, range: (0.0, 20.0)),
+            (input: Health),
+            (input: Task(Cover)),
+        ],
+    )"#;
+
+    fn scores(goal: &GoalComponent, shared: &mut ThinkerShared) -> (bool, Vec<f32>) {
+        let mut ai_nodes = None;
+        let context = AgentGoalWorldContext {
+            id: &0,
+            working_memory: &mut shared.working_memory,
+            current_world_state: &mut shared.world_state,
+            blackboard: &mut shared.blackboard,
+            ai_nodes: &mut ai_nodes,
+        };
+        let is_valid = goal.goal_type.is_valid(goal, &context);
+        (is_valid, goal.relevance(0, &context).considerations)
+    }
+
+    #[test]
+    fn test_snapshot_keeps_consideration_inputs() {
+        let goal: GoalComponent = ron::from_str(CHASE_ENEMY).unwrap();
+        let target = InstanceId::from_i64(1);
+        let clock = GameClock::default();
+        let mut working_memory = WorkingMemory::default().with_clock(clock.clone());
+        working_memory.add_working_memory_fact(
+            WMProperty::Knowledge(Knowledge::Character(target.into(), None)),
+            1.0,
+            60.0,
+        );
+        clock.advance(15.0);
+        let mut recorded = ThinkerShared {
+            working_memory,
+            blackboard: Blackboard {
+                target: Some(AITarget::Character(target, None)),
+                distance_to_target: Some(5.0),
+                health: Some(0.5),
+                squad_role: Some(SquadRole::Hold),
+                task: Some(Task::Cover),
+                ..Default::default()
+            },
+            world_state: WorldState::default(),
+            target_mask: Default::default(),
+        };
+        let snapshot = ThinkerSnapshot {
+            world_state: recorded.world_state.clone(),
+            facts: recorded.working_memory.snapshot(),
+            blackboard: BlackboardSnapshot::from(&recorded.blackboard),
+            fact_policies: recorded.working_memory.policies(),
+            ai_nodes: Vec::new(),
+        };
+        let line = ron::to_string(&snapshot).unwrap();
+        let mut replayed = ron::from_str::<ThinkerSnapshot>(&line).unwrap().restore();
+
+        let (recorded_valid, recorded_scores) = scores(&goal, &mut recorded);
+        let (replayed_valid, replayed_scores) = scores(&goal, &mut replayed);
+        // goal is invalid for the thinker told to hold
+        assert!(!recorded_valid);
+        assert_eq!(recorded_valid, replayed_valid);
+        assert_eq!(recorded_scores.len(), replayed_scores.len());
+        for (recorded, replayed) in recorded_scores.iter().zip(replayed_scores.iter()) {
+            assert!((recorded - replayed).abs() < 1e-4);
+        }
+        assert!((recorded_scores[0] - 0.75).abs() < 1e-4);
+        assert_eq!(replayed_scores[3], 1.0);
+    }
+
+    #[test]
+    fn test_snapshot_keeps_ai_nodes() {
+        let cover = AINode::Cover {
+            base: AINodeBase {
+                ainode_id: 7,
+                base_id: InstanceId::from_i64(1),
+                position: Vector3::ZERO,
+                status: AtomicU32::new(0),
+            },
+            cone: CoverCone {
+                direction: Vector3::FORWARD,
+                half_angle: 0.5,
+            },
+        };
+        // locked by another thinker
+        assert!(cover.base().try_lock(2));
+        let snapshot = AINodeSnapshot::capture(&cover).unwrap();
+        let line = ron::to_string(&snapshot).unwrap();
+        let restored = ron::from_str::<AINodeSnapshot>(&line).unwrap().restore();
+
+        assert_eq!(restored.base().ainode_id, 7);
+        assert!(restored.is_locked_not_by(1));
+        let in_front = Vector3::FORWARD * 10.0;
+        assert_eq!(
+            restored.is_valid_against(in_front),
+            cover.is_valid_against(in_front)
+        );
+        assert!(!restored.is_valid_against(Vector3::BACK * 10.0));
+        // replay locks its own copy of the node
+        restored.base().unlock(2);
+        assert!(cover.is_locked());
+    }
+}
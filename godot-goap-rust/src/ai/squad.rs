@@ -0,0 +1,281 @@
+// Squads let thinkers share their knowledge & coordinate their actions.
+// Members publish selected facts into the squad working memory, which are then shared with the rest of the squad.
+
+use crate::ai::thinker::{Thinker, ThinkerShared};
+use crate::ai::working_memory::{
+    FactPolicy, FactQuery, FactQueryCheck, Reinforcement, Task, WMKnowledgeType, WorkingMemory,
+    WorkingMemoryFactTypeKey,
+};
+use crate::targeting::targeting_systems::TargetMask;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// role assigned to a squad member by the coordinator
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SquadRole {
+    /// approaches the target from the side
+    Flank,
+    /// keeps the target busy, so the others can advance
+    Suppress,
+    /// stays in place instead of rushing the target
+    Hold,
+}
+
+impl SquadRole {
+    /// task given to the squad member with this role
+    pub fn task(&self) -> Option<Task> {
+        match self {
+            SquadRole::Flank => Some(Task::Advance),
+            SquadRole::Suppress => Some(Task::Cover),
+            SquadRole::Hold => None,
+        }
+    }
+}
+
+/// assigns roles to the squad members engaging a target, configured in the squads file
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(default)]
+pub struct SquadCoordinator {
+    /// number of members closest to the target that are sent to flank it
+    pub flankers: usize,
+    /// number of members suppressing the target, the rest of them holds
+    pub suppressors: usize,
+    /// roles are reassigned every given number of seconds, so they don't flicker
+    pub update_every: f64,
+    /// members keep their roles unless the others get closer to the target
+    /// by more than given distance per each rank between their roles
+    pub role_hysteresis: f32,
+    #[serde(skip)]
+    last_update: Option<f64>,
+}
+
+impl Default for SquadCoordinator {
+    fn default() -> Self {
+        SquadCoordinator {
+            flankers: 1,
+            suppressors: 1,
+            update_every: 1.0,
+            role_hysteresis: 2.0,
+            last_update: None,
+        }
+    }
+}
+
+impl SquadCoordinator {
+    /// assigns roles to the members engaging a target, at most once every `update_every` seconds
+    pub fn assign_roles(&mut self, members: &[&Thinker], now: f64) {
+        if self
+            .last_update
+            .is_some_and(|last_update| now - last_update < self.update_every)
+        {
+            return;
+        }
+        self.last_update = Some(now);
+
+        // members engaging a target, closest first. Members with higher roles are treated as closer,
+        // so the ones at similar distances don't keep swapping their roles.
+        let mut engaging: Vec<(f32, &Thinker)> = Vec::with_capacity(members.len());
+        for member in members.iter() {
+            let Ok(mut shared) = member.shared.lock() else {
+                panic!("mutex failed – couldn't assign squad role!")
+            };
+            let distance = shared.blackboard.target.as_ref().and_then(|target| {
+                target
+                    .get_target_pos()
+                    .map(|pos| pos.distance_to(shared.blackboard.thinker_position))
+            });
+            let rank = match shared.blackboard.squad_role {
+                Some(SquadRole::Flank) => 2.0,
+                Some(SquadRole::Suppress) => 1.0,
+                Some(SquadRole::Hold) | None => 0.0,
+            };
+            match distance {
+                Some(distance) => engaging.push((distance - rank * self.role_hysteresis, *member)),
+                None => set_role(&mut shared, None),
+            }
+        }
+        engaging.sort_by(|(a, _), (b, _)| a.total_cmp(b));
+
+        for (idx, (_distance, member)) in engaging.into_iter().enumerate() {
+            let role = if idx < self.flankers {
+                SquadRole::Flank
+            } else if idx < self.flankers + self.suppressors {
+                SquadRole::Suppress
+            } else {
+                SquadRole::Hold
+            };
+            let Ok(mut shared) = member.shared.lock() else {
+                panic!("mutex failed – couldn't assign squad role!")
+            };
+            set_role(&mut shared, Some(role));
+        }
+    }
+}
+
+fn set_role(shared: &mut ThinkerShared, role: Option<SquadRole>) {
+    if shared.blackboard.squad_role == role {
+        return;
+    }
+    shared.blackboard.squad_role = role;
+    shared.blackboard.task = role.and_then(|role| role.task());
+    shared.blackboard.invalidate_plan = true;
+}
+
+#[derive(Debug)]
+pub struct Squad {
+    pub members: Vec<u32>,
+    /// facts published by the members
+    pub working_memory: WorkingMemory,
+    /// facts of the members passing any of these checks are shared with the whole squad
+    pub shared_facts: Vec<FactQueryCheck>,
+    pub coordinator: SquadCoordinator,
+}
+
+impl Default for Squad {
+    fn default() -> Self {
+        let policies = HashMap::from([(
+            WorkingMemoryFactTypeKey::Knowledge,
+            FactPolicy {
+                decay: None,
+                reinforcement: Reinforcement::Max,
+            },
+        )]);
+        Squad {
+            members: Vec::new(),
+            working_memory: WorkingMemory::default().with_policies(&policies),
+            shared_facts: vec![
+                FactQueryCheck::Knowledge(WMKnowledgeType::Character),
+                FactQueryCheck::Knowledge(WMKnowledgeType::LastTargetPosition),
+            ],
+            coordinator: Default::default(),
+        }
+    }
+}
+
+impl Squad {
+    pub fn process(&mut self, thinkers: &HashMap<u32, Thinker>) {
+        self.working_memory.validate();
+        let members: Vec<&Thinker> = self
+            .members
+            .iter()
+            .filter_map(|id| thinkers.get(id))
+            .filter(|thinker| thinker.is_active)
+            .collect();
+        for member in members.iter() {
+            let Ok(shared) = member.shared.lock() else {
+                panic!("mutex failed – couldn't publish squad facts!")
+            };
+            self.publish(&shared.working_memory);
+        }
+        for member in members.iter() {
+            let Ok(mut shared) = member.shared.lock() else {
+                panic!("mutex failed – couldn't share squad facts!")
+            };
+            self.share(&mut shared);
+        }
+        self.coordinator
+            .assign_roles(&members, self.working_memory.now());
+    }
+
+    fn publish(&mut self, memory: &WorkingMemory) {
+        for check in self.shared_facts.iter() {
+            for fact in memory.find_facts(FactQuery::with_check(check.clone())) {
+                self.working_memory.merge(fact);
+            }
+        }
+    }
+
+    /// gives the member squad facts it doesn't know about yet.
+    /// Facts already known by the member are left untouched & shared facts keep their update time,
+    /// so the members don't keep refreshing each other.
+    fn share(&self, shared: &mut ThinkerShared) {
+        let mut is_new_character_known = false;
+        for check in self.shared_facts.iter() {
+            for fact in self
+                .working_memory
+                .find_facts(FactQuery::with_check(check.clone()))
+            {
+                let query = FactQuery::with_check(FactQueryCheck::Match(fact.f_type.clone()));
+                if shared.working_memory.find_fact(query).is_some() {
+                    continue;
+                }
+                // member has lost the character after the squad has seen it last time
+                if let Some(character) = fact.f_type.character() {
+                    if let Some(lost_at) = shared.blackboard.lost_characters.get(&character) {
+                        if fact.update_time <= *lost_at {
+                            continue;
+                        }
+                        shared.blackboard.lost_characters.remove(&character);
+                    }
+                }
+                shared.working_memory.merge(fact);
+                is_new_character_known |= fact.f_type.character().is_some();
+            }
+        }
+        if is_new_character_known {
+            shared.blackboard.invalidate_target = true;
+            shared.blackboard.valid_targets = shared
+                .blackboard
+                .valid_targets
+                .union(TargetMask::VisibleCharacter);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::targeting::target::AITarget;
+    use godot::builtin::Vector3;
+
+    fn member(distance: f32) -> Thinker {
+        let thinker = Thinker::default();
+        {
+            let mut shared = thinker.shared.lock().unwrap();
+            shared.blackboard.target = Some(AITarget::Disturbance(Vector3::ZERO));
+            shared.blackboard.thinker_position = Vector3::new(distance, 0.0, 0.0);
+        }
+        thinker
+    }
+
+    fn move_member(member: &Thinker, distance: f32) {
+        member.shared.lock().unwrap().blackboard.thinker_position =
+            Vector3::new(distance, 0.0, 0.0);
+    }
+
+    fn role(member: &Thinker) -> Option<SquadRole> {
+        member.shared.lock().unwrap().blackboard.squad_role
+    }
+
+    #[test]
+    fn test_role_hysteresis() {
+        let mut coordinator = SquadCoordinator {
+            suppressors: 0,
+            ..Default::default()
+        };
+        let members = [member(5.0), member(6.0)];
+        let members: Vec<&Thinker> = members.iter().collect();
+        coordinator.assign_roles(&members, 0.0);
+        assert_eq!(role(members[0]), Some(SquadRole::Flank));
+        assert_eq!(role(members[1]), Some(SquadRole::Hold));
+        assert_eq!(
+            members[0].shared.lock().unwrap().blackboard.task,
+            Some(Task::Advance)
+        );
+
+        // flanker is slightly further than the other member now
+        move_member(members[0], 7.0);
+        coordinator.assign_roles(&members, 1.0);
+        assert_eq!(role(members[0]), Some(SquadRole::Flank));
+
+        // roles aren't reassigned more often than every `update_every` seconds
+        move_member(members[0], 20.0);
+        coordinator.assign_roles(&members, 1.5);
+        assert_eq!(role(members[0]), Some(SquadRole::Flank));
+
+        coordinator.assign_roles(&members, 2.0);
+        assert_eq!(role(members[0]), Some(SquadRole::Hold));
+        assert_eq!(role(members[1]), Some(SquadRole::Flank));
+        assert_eq!(members[0].shared.lock().unwrap().blackboard.task, None);
+    }
+}
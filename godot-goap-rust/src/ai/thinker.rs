@@ -1,7 +1,10 @@
+use crate::ai::ai_stimulus::AIStimulus;
 use crate::ai::blackboard::Blackboard;
+use crate::ai::planner::{PlanBudget, SearchMode};
 use crate::ai::working_memory::WorkingMemory;
 use crate::ai::world_state::WorldState;
 use crate::animations::animation_data::AnimationsData;
+use crate::attacks::attack_data::AttackData;
 use crate::goap_actions::action_component::ActionComponent;
 use crate::goap_goals::goal_component::GoalComponent;
 use crate::godot_api::godot_thinker::GodotThinker;
@@ -28,8 +31,13 @@ pub struct Thinker {
     pub goals: Arc<Vec<GoalComponent>>,
     pub actions: Arc<Vec<ActionComponent>>,
     pub animations: Arc<AnimationsData>,
+    pub attacks: Arc<Vec<AttackData>>,
+    pub search_mode: SearchMode,
+    pub plan_budget: PlanBudget,
     pub polling_sensors: Vec<PollingSensor>,
     pub event_sensor: Vec<EventSensor>,
+    /// stimuli queued by the AIManager, delivered to the event sensors during the next process
+    pub stimuli: Vec<AIStimulus>,
     pub navigation_map_rid: Option<Rid>,
     pub navigation_data: Navigator,
 }
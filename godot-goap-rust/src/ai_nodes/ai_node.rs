@@ -13,23 +13,38 @@ pub enum AINode {
         next: Option<u32>,
         orientation: Option<Vector3>,
     },
+    /// protects from the targets inside its cone
+    Cover { base: AINodeBase, cone: CoverCone },
+    /// conceals the thinker from the targets in any direction
+    Hide { base: AINodeBase },
+    /// overlooks the targets approaching from inside its cone
+    Ambush { base: AINodeBase, cone: CoverCone },
+    /// advertises an activity thinkers can perform on it
+    SmartObject {
+        base: AINodeBase,
+        interaction: SmartObjectInteraction,
+    },
 }
 
 impl AINode {
     pub fn base(&self) -> &AINodeBase {
         match self {
-            AINode::Patrol { base, .. } => base,
-            _ => {
-                todo!()
-            }
+            AINode::Patrol { base, .. }
+            | AINode::Cover { base, .. }
+            | AINode::Hide { base }
+            | AINode::Ambush { base, .. }
+            | AINode::SmartObject { base, .. } => base,
+            AINode::None => panic!("invalid ainode has no base!"),
         }
     }
     pub fn base_mut(&mut self) -> &mut AINodeBase {
         match self {
-            AINode::Patrol { base, .. } => base,
-            _ => {
-                todo!()
-            }
+            AINode::Patrol { base, .. }
+            | AINode::Cover { base, .. }
+            | AINode::Hide { base }
+            | AINode::Ambush { base, .. }
+            | AINode::SmartObject { base, .. } => base,
+            AINode::None => panic!("invalid ainode has no base!"),
         }
     }
     pub fn with_dependency(self, dependency: u32) -> Self {
@@ -51,23 +66,55 @@ impl AINode {
     }
 
     pub fn is_locked_not_by(&self, not_by: u32) -> bool {
+        let val = self.base().status.load(Ordering::Acquire);
+        val != 0 && val != not_by
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.base().is_locked()
+    }
+
+    /// true if the node protects the thinker standing on it from a target at given position
+    pub fn is_valid_against(&self, target_position: Vector3) -> bool {
         match self {
-            AINode::Patrol { base, .. } => {
-                let val = base.status.load(Ordering::Acquire);
-                !((val != not_by) || (val == 0))
+            AINode::Cover { base, cone } | AINode::Ambush { base, cone } => {
+                cone.contains(base.position, target_position)
             }
-            _ => todo!(),
+            AINode::Hide { .. } => true,
+            AINode::Patrol { .. } | AINode::SmartObject { .. } | AINode::None => false,
         }
     }
+}
 
-    pub fn is_locked(&self) -> bool {
-        match self {
-            AINode::Patrol { base, .. } => base.is_locked(),
-            _ => todo!(),
+/// directions covered by the node
+#[derive(Debug, Clone, Copy)]
+pub struct CoverCone {
+    pub direction: Vector3,
+    /// in radians
+    pub half_angle: f32,
+}
+
+impl CoverCone {
+    pub fn contains(&self, origin: Vector3, point: Vector3) -> bool {
+        let to_point = point - origin;
+        if to_point.is_zero_approx() {
+            return false;
         }
+        self.direction.angle_to(to_point) <= self.half_angle
     }
 }
 
+/// activity advertised by the SmartObject node
+#[derive(Debug, Clone)]
+pub struct SmartObjectInteraction {
+    /// animation tree state played by the user
+    pub animation: String,
+    /// 0 – until the animation completes
+    pub duration: f64,
+    /// object being used, faced by the user
+    pub object: Option<InstanceId>,
+}
+
 #[derive(Debug, Default)]
 pub enum AINodeStatus {
     #[default]
@@ -97,15 +144,43 @@ impl From<&GodotAINode> for AINode {
                     .as_ref()
                     .map(|on| on.get_global_position()),
             },
-            AINodeType::Hide => {
-                todo!()
-            }
-            AINodeType::Ambush => {
-                todo!()
-            }
-            AINodeType::Cover => {
-                todo!()
-            }
+            AINodeType::Hide => AINode::Hide { base: inner },
+            AINodeType::Ambush => AINode::Ambush {
+                base: inner,
+                cone: CoverCone::from(value),
+            },
+            AINodeType::Cover => AINode::Cover {
+                base: inner,
+                cone: CoverCone::from(value),
+            },
+            AINodeType::SmartObject => AINode::SmartObject {
+                base: inner,
+                interaction: SmartObjectInteraction {
+                    animation: value.use_animation.to_string(),
+                    duration: value.use_duration,
+                    object: value
+                        .animatable_object
+                        .as_ref()
+                        .map(|object| object.instance_id()),
+                },
+            },
+        }
+    }
+}
+
+impl From<&GodotAINode> for CoverCone {
+    /// covered direction points towards the orientation node, or along the Z axis of the node
+    fn from(value: &GodotAINode) -> Self {
+        let position = value.base().get_global_position();
+        let direction = value
+            .orientation_node
+            .as_ref()
+            .map(|on| on.get_global_position() - position)
+            .filter(|direction| !direction.is_zero_approx())
+            .unwrap_or_else(|| value.base().get_global_basis().col_c());
+        CoverCone {
+            direction: direction.normalized(),
+            half_angle: value.cover_angle.to_radians() / 2.0,
         }
     }
 }
@@ -123,4 +198,22 @@ impl AINodeBase {
     pub fn is_locked(&self) -> bool {
         self.status.load(Ordering::Acquire) != 0
     }
+
+    /// locks the node for given agent. Returns false if it is already locked by someone else
+    pub fn try_lock(&self, agent_id: u32) -> bool {
+        match self
+            .status
+            .compare_exchange(0, agent_id, Ordering::AcqRel, Ordering::Acquire)
+        {
+            Ok(_) => true,
+            Err(locked_by) => locked_by == agent_id,
+        }
+    }
+
+    /// frees the node if it is locked by given agent
+    pub fn unlock(&self, agent_id: u32) {
+        let _ = self
+            .status
+            .compare_exchange(agent_id, 0, Ordering::AcqRel, Ordering::Acquire);
+    }
 }
@@ -1,3 +1,4 @@
+use crate::act_react::act_react_resource::ActReactResource;
 use crate::ai_nodes::ai_node::AINodeStatus;
 use crate::godot_api::ai_manager::GodotAIManager;
 use crate::godot_api::gamesys::GameSystem;
@@ -13,6 +14,7 @@ pub enum AINodeType {
     Hide,
     Ambush,
     Cover,
+    SmartObject,
 }
 
 #[derive(GodotClass)]
@@ -28,8 +30,22 @@ pub struct GodotAINode {
     pub dependency: Option<Gd<GodotAINode>>,
     #[export]
     pub orientation_node: Option<Gd<Marker3D>>,
+    /// angle, in degrees, of the cone protected by the Cover & Ambush nodes
+    #[export(range = (0.0, 360.0))]
+    #[init(val = 90.0)]
+    pub cover_angle: f32,
+    /// object used by the thinker standing on the SmartObject node
     #[export]
     pub animatable_object: Option<Gd<Node3D>>,
+    /// animation tree state played by the thinker using the SmartObject node
+    #[export]
+    pub use_animation: GString,
+    /// number of seconds the SmartObject node is being used for, 0 – until the animation completes
+    #[export]
+    pub use_duration: f64,
+    /// reactions to being used, triggered by the Frob stimuli
+    #[export]
+    pub use_reaction: Option<Gd<ActReactResource>>,
     pub ainode_status: AINodeStatus,
     pub base: Base<Area3D>,
 }
@@ -0,0 +1,142 @@
+use crate::ai::blackboard::Blackboard;
+use crate::ai::types::EntityId;
+use crate::ai::working_memory::Event::AttackPerformed;
+use crate::ai::working_memory::{AIStimuli, FactQuery, FactQueryCheck, WMProperty, WorkingMemory};
+use crate::ai::world_state::WorldState;
+use crate::animations::animation_data::AnimationProps;
+use crate::targeting::target::AITarget;
+use serde::{Deserialize, Serialize};
+
+/// an attack that can be performed by a thinker, loaded from the `*_attacks.ron` file
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct AttackData {
+    pub name: String,
+    /// world state that must be met in order to perform given attack
+    pub required_state: WorldState,
+    pub validators: Vec<AttackValidator>,
+    pub default_weight: f32,
+    /// random modifier added to the default weight every time the attack is being picked
+    pub weight_range: (f32, f32),
+    /// animations played one after another while performing the attack
+    pub steps: Vec<AnimationProps>,
+}
+
+impl AttackData {
+    pub fn is_valid(
+        &self,
+        attack_idx: usize,
+        world_state: &WorldState,
+        working_memory: &WorkingMemory,
+        blackboard: &Blackboard,
+    ) -> bool {
+        self.required_state
+            .count_unsatisfied_world_state_props(world_state)
+            == 0
+            && self
+                .validators
+                .iter()
+                .all(|validator| validator.is_valid(attack_idx, working_memory, blackboard))
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum AttackValidator {
+    /// attack can't be repeated before given time (in seconds) passes
+    AttackCooldown { cooldown: f64 },
+    /// character target is visible and within given distance
+    AttackVisibleWithinRange { min: f32, max: f32 },
+}
+
+impl AttackValidator {
+    pub fn is_valid(
+        &self,
+        attack_idx: usize,
+        working_memory: &WorkingMemory,
+        blackboard: &Blackboard,
+    ) -> bool {
+        match self {
+            AttackValidator::AttackCooldown { cooldown } => {
+                let query = FactQuery::with_check(FactQueryCheck::Match(WMProperty::Event(
+                    AttackPerformed { id: attack_idx },
+                )));
+                working_memory
+                    .find_fact(query)
+                    .map(|fact| working_memory.now() - fact.update_time >= *cooldown)
+                    .unwrap_or(true)
+            }
+            AttackValidator::AttackVisibleWithinRange { min, max } => {
+                let Some(AITarget::Character(target_id, _)) = &blackboard.target else {
+                    return false;
+                };
+                let query = FactQuery::with_check(FactQueryCheck::Match(WMProperty::AIStimuli(
+                    AIStimuli::Character(EntityId::from(*target_id), None),
+                )));
+                let is_visible = working_memory.find_fact(query).is_some();
+                is_visible
+                    && blackboard
+                        .distance_to_target
+                        .map(|distance| (*min..=*max).contains(&distance))
+                        .unwrap_or(false)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ai::clock::GameClock;
+    use godot::obj::InstanceId;
+
+    #[test]
+    fn test_attack_cooldown() {
+        let validator = AttackValidator::AttackCooldown { cooldown: 2.0 };
+        let clock = GameClock::default();
+        let mut working_memory = WorkingMemory::default().with_clock(clock.clone());
+        let blackboard = Blackboard::default();
+        assert!(validator.is_valid(0, &working_memory, &blackboard));
+
+        working_memory.add_working_memory_fact(
+            WMProperty::Event(AttackPerformed { id: 0 }),
+            1.0,
+            10.0,
+        );
+        clock.advance(1.0);
+        assert!(!validator.is_valid(0, &working_memory, &blackboard));
+        // cooldowns of other attacks are independent
+        assert!(validator.is_valid(1, &working_memory, &blackboard));
+        clock.advance(1.0);
+        assert!(validator.is_valid(0, &working_memory, &blackboard));
+    }
+
+    #[test]
+    fn test_attack_visible_within_range() {
+        let validator = AttackValidator::AttackVisibleWithinRange { min: 1.0, max: 5.0 };
+        let target = InstanceId::from_i64(1);
+        let mut working_memory = WorkingMemory::default();
+        let mut blackboard = Blackboard {
+            target: Some(AITarget::Character(target, None)),
+            distance_to_target: Some(3.0),
+            ..Default::default()
+        };
+        // target must be visible
+        assert!(!validator.is_valid(0, &working_memory, &blackboard));
+
+        working_memory.add_working_memory_fact(
+            WMProperty::AIStimuli(AIStimuli::Character(EntityId::from(target), None)),
+            1.0,
+            10.0,
+        );
+        assert!(validator.is_valid(0, &working_memory, &blackboard));
+        for distance in [0.5, 5.5] {
+            blackboard.distance_to_target = Some(distance);
+            assert!(!validator.is_valid(0, &working_memory, &blackboard));
+        }
+        for distance in [1.0, 5.0] {
+            blackboard.distance_to_target = Some(distance);
+            assert!(validator.is_valid(0, &working_memory, &blackboard));
+        }
+        blackboard.distance_to_target = None;
+        assert!(!validator.is_valid(0, &working_memory, &blackboard));
+    }
+}
@@ -0,0 +1,2 @@
+pub mod attack_data;
+pub mod process_attacks;
@@ -0,0 +1,113 @@
+use crate::ai::blackboard::Blackboard;
+use crate::ai::working_memory::WorkingMemory;
+use crate::ai::world_state::WSProperty::Truth;
+use crate::ai::world_state::{WorldState, WorldStateProperty};
+use crate::attacks::attack_data::AttackData;
+use rand::prelude::*;
+use rand::rng;
+
+/// updates attacks that can be performed right now and picks one of them if none has been chosen yet
+pub fn process_attacks(
+    attacks: &[AttackData],
+    world_state: &mut WorldState,
+    working_memory: &WorkingMemory,
+    blackboard: &mut Blackboard,
+) {
+    if attacks.is_empty() {
+        return;
+    }
+    blackboard.attack_candidates = attacks
+        .iter()
+        .enumerate()
+        .filter(|(idx, attack)| attack.is_valid(*idx, world_state, working_memory, blackboard))
+        .map(|(idx, _attack)| idx)
+        .collect();
+
+    if blackboard.invalidate_attack {
+        blackboard.invalidate_attack = false;
+        blackboard.chosen_attack_idx = None;
+    }
+    // attack that is being performed can't be changed
+    if !blackboard.attack_in_progress {
+        let is_chosen_valid = blackboard
+            .chosen_attack_idx
+            .map(|idx| blackboard.attack_candidates.contains(&idx))
+            .unwrap_or(false);
+        if !is_chosen_valid {
+            blackboard.chosen_attack_idx = pick_attack(attacks, &blackboard.attack_candidates);
+        }
+    }
+    world_state[WorldStateProperty::HasAttack] =
+        Some(Truth(blackboard.chosen_attack_idx.is_some()));
+}
+
+/// weighted random selection among given candidates
+fn pick_attack(attacks: &[AttackData], candidates: &[usize]) -> Option<usize> {
+    let mut rng = rng();
+    let weights: Vec<(usize, f32)> = candidates
+        .iter()
+        .map(|idx| {
+            let attack = &attacks[*idx];
+            let (min, max) = attack.weight_range;
+            let modifier = if min < max {
+                rng.random_range(min..max)
+            } else {
+                min
+            };
+            (*idx, (attack.default_weight + modifier).max(0.0))
+        })
+        .collect();
+    let total: f32 = weights.iter().map(|(_idx, weight)| weight).sum();
+    if total <= 0.0 {
+        return candidates.first().copied();
+    }
+    let mut roll = rng.random_range(0.0..total);
+    for (idx, weight) in weights.iter() {
+        if roll < *weight {
+            return Some(*idx);
+        }
+        roll -= weight;
+    }
+    weights.last().map(|(idx, _weight)| *idx)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn attack(default_weight: f32, weight_range: (f32, f32)) -> AttackData {
+        AttackData {
+            name: "Attack".to_string(),
+            required_state: WorldState::default(),
+            validators: Vec::new(),
+            default_weight,
+            weight_range,
+            steps: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_attacks_without_weight_are_never_picked() {
+        let attacks = [
+            attack(0.0, (0.0, 0.0)),
+            attack(-2.0, (0.0, 1.0)),
+            attack(1.0, (0.0, 0.5)),
+            attack(0.5, (-1.0, -0.5)),
+        ];
+        for _ in 0..100 {
+            assert_eq!(pick_attack(&attacks, &[0, 1, 2, 3]), Some(2));
+        }
+        // the first candidate is picked if none of them has any weight
+        assert_eq!(pick_attack(&attacks, &[0, 1]), Some(0));
+        assert_eq!(pick_attack(&attacks, &[]), None);
+    }
+
+    #[test]
+    fn test_picking_by_weight() {
+        let attacks = [attack(1.0, (0.0, 0.0)), attack(3.0, (0.0, 0.0))];
+        let picks = (0..1000)
+            .filter(|_| pick_attack(&attacks, &[0, 1]) == Some(1))
+            .count();
+        assert!((600..900).contains(&picks));
+    }
+}
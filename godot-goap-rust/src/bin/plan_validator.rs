@@ -0,0 +1,203 @@
+// Headless validator for the AI data sets.
+// Loads `*_actions.ron`, `*_goals.ron` and `*_initial_state.ron` files, reports parse errors
+// and checks if a plan exists for every goal.
+//
+// usage: plan_validator <file or directory>...
+// files are grouped into data sets by their prefix, e.g. `fishoid_actions.ron` & `fishoid_goals.ron`.
+
+use godot_goap_rust::ai::planner::{plan, PlanAction};
+use godot_goap_rust::ai::world_state::WorldState;
+use godot_goap_rust::goap_actions::action_component::ActionComponent;
+use godot_goap_rust::goap_actions::compound_action::resolve_compound_steps;
+use godot_goap_rust::goap_goals::goal_component::GoalComponent;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+/// planner view of an action that skips procedural preconditions – these require a running engine.
+#[derive(Debug, PartialEq, Hash)]
+struct HeadlessAction<'a>(&'a ActionComponent);
+
+impl PlanAction<()> for HeadlessAction<'_> {
+    fn get_action_preconditions(&self) -> &WorldState {
+        &self.0.preconditions
+    }
+
+    fn check_action_procedural_preconditions(&self, _action_arguments: &()) -> bool {
+        true
+    }
+
+    fn get_action_effects<'a, 'b: 'a>(&'a self, _action_arguments: &'b ()) -> &'a WorldState {
+        &self.0.effects
+    }
+
+    fn get_action_cost(&self, _action_arguments: &()) -> u32 {
+        self.0.cost
+    }
+}
+
+#[derive(Default, Debug)]
+struct DataSet {
+    actions: Option<PathBuf>,
+    goals: Option<PathBuf>,
+    initial_state: Option<PathBuf>,
+}
+
+impl DataSet {
+    const ACTIONS_SUFFIX: &'static str = "_actions";
+    const GOALS_SUFFIX: &'static str = "_goals";
+    const INITIAL_STATE_SUFFIX: &'static str = "_initial_state";
+
+    /// puts given file into the data set it belongs to. Returns false if file is not a part of any data set.
+    fn insert(data_sets: &mut BTreeMap<String, DataSet>, path: PathBuf) -> bool {
+        let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else {
+            return false;
+        };
+        let (name, slot): (&str, fn(&mut DataSet) -> &mut Option<PathBuf>) =
+            if let Some(name) = stem.strip_suffix(Self::ACTIONS_SUFFIX) {
+                (name, |set| &mut set.actions)
+            } else if let Some(name) = stem.strip_suffix(Self::GOALS_SUFFIX) {
+                (name, |set| &mut set.goals)
+            } else if let Some(name) = stem.strip_suffix(Self::INITIAL_STATE_SUFFIX) {
+                (name, |set| &mut set.initial_state)
+            } else {
+                return false;
+            };
+        let data_set = data_sets.entry(name.to_string()).or_default();
+        *slot(data_set) = Some(path);
+        true
+    }
+}
+
+fn load<T: for<'a> Deserialize<'a>>(path: &Path) -> Result<T, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("{}: couldn't read: {}", path.display(), e))?;
+    ron::from_str::<T>(&content).map_err(|e| {
+        format!(
+            "{}:{}:{}: {}",
+            path.display(),
+            e.position.line,
+            e.position.col,
+            e.code
+        )
+    })
+}
+
+fn collect_data_sets(args: impl Iterator<Item = String>) -> BTreeMap<String, DataSet> {
+    let mut data_sets: BTreeMap<String, DataSet> = BTreeMap::new();
+    for arg in args {
+        let path = PathBuf::from(arg);
+        if !path.is_dir() {
+            if !DataSet::insert(&mut data_sets, path.clone()) {
+                eprintln!(
+                    "{}: skipped – not an actions, goals or initial state file",
+                    path.display()
+                );
+            }
+            continue;
+        }
+        let Ok(entries) = fs::read_dir(&path) else {
+            eprintln!("{}: couldn't read directory", path.display());
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok().map(|e| e.path()))
+            .filter(|p| p.extension().map(|ext| ext == "ron").unwrap_or(false))
+            .collect();
+        files.sort();
+        for file in files {
+            DataSet::insert(&mut data_sets, file);
+        }
+    }
+    data_sets
+}
+
+/// returns parsed value, or reports a parse error
+fn report<T>(result: Result<T, String>, problems: &mut u32) -> Option<T> {
+    match result {
+        Ok(v) => Some(v),
+        Err(e) => {
+            println!("  parse error: {}", e);
+            *problems += 1;
+            None
+        }
+    }
+}
+
+/// validates given data set. Returns the number of found problems.
+fn validate(name: &str, data_set: &DataSet) -> u32 {
+    println!("== {}", name);
+    let mut problems: u32 = 0;
+    let actions: Option<Vec<ActionComponent>> = data_set.actions.as_ref().and_then(|p| {
+        let actions = load::<Vec<ActionComponent>>(p).and_then(|mut actions| {
+            resolve_compound_steps(&mut actions).map_err(|e| format!("{}: {}", p.display(), e))?;
+            Ok(actions)
+        });
+        report(actions, &mut problems)
+    });
+    let goals: Option<Vec<GoalComponent>> = data_set
+        .goals
+        .as_ref()
+        .and_then(|p| report(load::<Vec<GoalComponent>>(p), &mut problems));
+    // initial state file is optional – thinker might start with an empty world state
+    let initial_state: Option<WorldState> = match data_set.initial_state.as_ref() {
+        Some(p) => report(load::<WorldState>(p), &mut problems),
+        None => Some(WorldState::default()),
+    };
+
+    let (Some(actions), Some(goals), Some(initial_state)) = (actions, goals, initial_state) else {
+        if data_set.actions.is_none() || data_set.goals.is_none() {
+            println!("  skipping plan validation – data set requires both actions and goals");
+        }
+        return problems;
+    };
+
+    let headless_actions: Vec<HeadlessAction> = actions.iter().map(HeadlessAction).collect();
+    for goal in goals.iter() {
+        // goal can be activated only if its required state is met
+        let mut start_state = initial_state.clone();
+        start_state.apply_world_state(&goal.required_state);
+        if goal
+            .desired_state
+            .count_unsatisfied_world_state_props(&start_state)
+            == 0
+        {
+            println!("  {}: already satisfied", goal.name);
+            continue;
+        }
+        match plan(&start_state, &goal.desired_state, &headless_actions, &()) {
+            Some(steps) => {
+                let names: Vec<&str> = steps
+                    .iter()
+                    .map(|step| step.action.0.name.as_str())
+                    .collect();
+                println!("  {}: {}", goal.name, names.join(" -> "));
+            }
+            None => {
+                println!("  {}: NO PLAN", goal.name);
+                problems += 1;
+            }
+        }
+    }
+    problems
+}
+
+fn main() -> ExitCode {
+    let data_sets = collect_data_sets(env::args().skip(1));
+    if data_sets.is_empty() {
+        eprintln!("usage: plan_validator <file or directory>...");
+        return ExitCode::FAILURE;
+    }
+    let problems: u32 = data_sets
+        .iter()
+        .map(|(name, data_set)| validate(name, data_set))
+        .sum();
+    if problems > 0 {
+        println!("{} problem(s) found", problems);
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
@@ -1,11 +1,13 @@
 use crate::act_react::act_react_executor::ActReactExecutor;
 use crate::act_react::act_react_resource::ActReactResource;
 use crate::act_react::react_area_3d::ActReactArea3D;
+use crate::ai::ai_stimulus::AIStimulusType;
 use crate::character_controler::character_controller_3d::CharacterController3D;
 use crate::equipment::equip_component::{
     Equipment, EquipmentComponent, EquipmentComponentResource, ItemEquipmentComponent,
 };
 use crate::equipment::gun_ui::GunDisplay;
+use crate::godot_api::ai_manager::GodotAIManager;
 use crate::godot_api::gamesys::{GameSys, GameSystem};
 use crate::godot_api::godot_inventory::InventoryAgent;
 use crate::godot_api::inventory_manager::InventoryManager;
@@ -123,6 +125,10 @@ pub struct SpreadGun {
     pub player: OnReady<Gd<PlayerController>>,
     #[export]
     ray_length: f32,
+    /// radius within which thinkers can hear the shot
+    #[export]
+    #[init(val = 40.0)]
+    noise_radius: f32,
     #[init(node = "AnimationPlayer")]
     animation_player: OnReady<Gd<AnimationPlayer>>,
     #[init(node = "../../Camera3D")]
@@ -130,6 +136,8 @@ pub struct SpreadGun {
     #[init(node = "anchor/Muzzle")]
     muzzle: OnReady<Gd<Marker3D>>,
     excluded: Array<Rid>,
+    /// character wielding the gun
+    owner: Option<Gd<CharacterController3D>>,
     /// direct pointer to given item component. Is valid as long as its Item is valid.
     eq_component: Option<*mut SpreadGunItemComponent>,
     base: Base<Node3D>,
@@ -350,6 +358,14 @@ impl SpreadGun {
             };
             self.apply_hitscan_collision(result, ammo);
         }
+        let position = self.base().get_global_position();
+        GodotAIManager::singleton().bind_mut().broadcast_stimulus(
+            AIStimulusType::WeaponFireSound,
+            position,
+            self.noise_radius,
+            1.0,
+            self.owner.clone().map(|owner| owner.upcast()),
+        );
         self.base_mut().emit_signal("shoot", &[]);
     }
 }
@@ -365,6 +381,7 @@ impl INode3D for SpreadGun {
             .unwrap()
             .cast::<CharacterController3D>();
         self.excluded.push(player.get_rid());
+        self.owner = Some(player);
         self.animation_player.play_ex().name("prepare").done();
         self.player.init(
             self.base()
@@ -1,6 +1,7 @@
-use crate::ai::planner::PlanAction;
+use crate::ai::planner::{ActionBinding, PlanAction};
 use crate::ai::world_state::WorldState;
 use crate::animations::animation_data::AnimationType;
+use crate::goap_actions::action_parameter::ActionParameter;
 use crate::goap_actions::action_types::ActionBehavior;
 use crate::goap_actions::action_types::{Action, ActionType, AgentActionPlanContext};
 use serde::{Deserialize, Serialize};
@@ -14,12 +15,23 @@ pub struct ActionComponent {
     pub effects: WorldState,
     pub animation: AnimationType,
     pub action_type: Action,
+    /// parameter bound at plan time, e.g. which attack to perform
+    #[serde(default)]
+    pub parameter: Option<ActionParameter>,
+    /// false for actions performed only as steps of the compound ones
+    #[serde(default = "is_plannable_default")]
+    pub is_plannable: bool,
+}
+
+fn is_plannable_default() -> bool {
+    true
 }
 
 impl Hash for ActionComponent {
     fn hash<H: Hasher>(&self, state: &mut H) {
         self.preconditions.hash(state);
         self.effects.hash(state);
+        self.parameter.hash(state);
     }
 }
 
@@ -33,11 +45,10 @@ impl PartialEq for ActionComponent {
             return false;
         }
 
-        let are_preconditions_the_same = self
-            .preconditions
-            .count_state_differences(&other.preconditions)
-            == 0
-            && self.effects.count_state_differences(&other.effects) == 0;
+        // compared structurally – predicates such as `AtLeast(2)` are satisfied by many different values
+        let are_preconditions_the_same = self.preconditions.inner == other.preconditions.inner
+            && self.effects.inner == other.effects.inner
+            && self.parameter == other.parameter;
 
         if !are_preconditions_the_same {
             return false;
@@ -71,4 +82,16 @@ impl PlanAction<AgentActionPlanContext<'_>> for ActionComponent {
     fn get_action_cost(&self, action_arguments: &AgentActionPlanContext<'_>) -> u32 {
         self.cost + self.action_type.get_cost(action_arguments)
     }
+
+    fn get_action_bindings(
+        &self,
+        action_arguments: &AgentActionPlanContext<'_>,
+    ) -> Option<Vec<(ActionBinding, u32)>> {
+        self.parameter
+            .map(|parameter| parameter.candidates(action_arguments))
+    }
+
+    fn is_action_plannable(&self) -> bool {
+        self.is_plannable
+    }
 }
@@ -0,0 +1,85 @@
+use crate::ai::blackboard::NavigationTarget;
+use crate::ai::planner::ActionBinding;
+use crate::ai::types::Vec3;
+use crate::ai::working_memory::{FactQuery, FactQueryCheck, Node, WMNodeType, WMProperty};
+use crate::goap_actions::action_types::{AgentActionPlanContext, AgentActionWorldContext};
+use serde::{Deserialize, Serialize};
+
+/// symbolic parameter of an action, bound by the planner to one of the available candidates.
+/// Allows to use a single action (e.g. "attack") with many different attacks or nodes.
+#[derive(Debug, Clone, Copy, Deserialize, Serialize, Eq, PartialEq, Hash)]
+pub enum ActionParameter {
+    /// one of the attacks that can be performed right now
+    Attack,
+    /// one of the known patrol nodes, the closest one is the cheapest
+    PatrolNode,
+    /// one of the known smart objects, the closest one is the cheapest
+    SmartObjectNode,
+}
+
+impl ActionParameter {
+    /// returns the candidates with their additional cost
+    pub fn candidates(
+        &self,
+        action_arguments: &AgentActionPlanContext,
+    ) -> Vec<(ActionBinding, u32)> {
+        match self {
+            ActionParameter::Attack => action_arguments
+                .blackboard
+                .attack_candidates
+                .iter()
+                .map(|idx| (ActionBinding::Attack(*idx), 0))
+                .collect(),
+            ActionParameter::PatrolNode => node_candidates(action_arguments, WMNodeType::Patrol),
+            ActionParameter::SmartObjectNode => {
+                node_candidates(action_arguments, WMNodeType::SmartObject)
+            }
+        }
+    }
+}
+
+/// known nodes of given type, the further away from the thinker the more expensive
+fn node_candidates(
+    action_arguments: &AgentActionPlanContext,
+    node_type: WMNodeType,
+) -> Vec<(ActionBinding, u32)> {
+    let thinker_position: Vec3 = action_arguments.blackboard.thinker_position.into();
+    let fact_query = FactQuery::with_check(FactQueryCheck::Node(node_type));
+    action_arguments
+        .working_memory
+        .find_facts(fact_query)
+        .filter_map(|fact| {
+            let WMProperty::Node(node) = &fact.f_type else {
+                return None;
+            };
+            let cost = thinker_position.distance_to(node.position()) as u32;
+            Some((ActionBinding::Node(node.ainode_id()), cost))
+        })
+        .collect()
+}
+
+/// exposes value bound by the planner to the action behaviour via the blackboard
+pub fn apply_binding(binding: &ActionBinding, action_arguments: &mut AgentActionWorldContext) {
+    match binding {
+        ActionBinding::Attack(idx) => action_arguments.blackboard.chosen_attack_idx = Some(*idx),
+        ActionBinding::Node(id) => {
+            let fact_query = FactQuery::with_check(FactQueryCheck::Node(WMNodeType::Patrol));
+            let position = action_arguments
+                .working_memory
+                .find_facts(fact_query)
+                .find_map(|fact| match &fact.f_type {
+                    WMProperty::Node(Node::Patrol {
+                        ainode_id,
+                        position,
+                    }) if ainode_id == id => Some(*position),
+                    _ => None,
+                });
+            if let Some(position) = position {
+                action_arguments.blackboard.navigation_target =
+                    Some(NavigationTarget::PatrolPoint(*id, position.into()));
+            }
+        }
+        // not bound to any action parameter yet
+        ActionBinding::Entity(_) => {}
+    }
+}
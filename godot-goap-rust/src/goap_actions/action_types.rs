@@ -2,15 +2,19 @@ use crate::ai::blackboard::Blackboard;
 use crate::ai::working_memory::WorkingMemory;
 use crate::ai::world_state::WorldState;
 use crate::ai_nodes::ai_node::AINode;
-use crate::animations::animation_data::AnimationsData;
+use crate::animations::animation_data::{AnimationType, AnimationsData};
+use crate::attacks::attack_data::AttackData;
 use crate::goap_actions::action_component::ActionComponent;
 use crate::goap_actions::aim_action::AimWeapon;
 use crate::goap_actions::animate_action::Animate;
 use crate::goap_actions::arm_weapon_action::ArmWeapon;
 use crate::goap_actions::attack_ranged_action::RangedAttack;
+use crate::goap_actions::compound_action::Compound;
 use crate::goap_actions::goto_action::GoTo;
 use crate::goap_actions::patrol_action::Patrol;
 use crate::goap_actions::release_weapon_action::ReleaseWeapon;
+use crate::goap_actions::search_action::Search;
+use crate::goap_actions::use_smart_object_action::UseSmartObject;
 use enum_dispatch::enum_dispatch;
 use godot::builtin::Rid;
 use serde::{Deserialize, Serialize};
@@ -20,11 +24,13 @@ use strum_macros::EnumDiscriminants;
 
 #[derive(Debug)]
 pub struct AgentActionWorldContext<'a> {
-    pub working_memory: &'a WorkingMemory,
+    pub id: &'a u32,
+    pub working_memory: &'a mut WorkingMemory,
     pub current_world_state: &'a mut WorldState,
     pub blackboard: &'a mut Blackboard,
     pub navigation_map_rid: Option<Rid>,
     pub animations: &'a Arc<AnimationsData>,
+    pub attacks: &'a Arc<Vec<AttackData>>,
     pub ai_nodes: &'a mut Option<Arc<RwLock<HashMap<u32, AINode>>>>,
 }
 
@@ -48,6 +54,9 @@ pub enum Action {
     AimWeapon,
     RangedAttack,
     ReleaseWeapon,
+    Compound,
+    Search,
+    UseSmartObject,
 }
 
 // #[allow(clippy::enum_variant_names)]
@@ -143,4 +152,9 @@ pub trait ActionBehavior {
     fn get_cost(&self, action_arguments: &AgentActionPlanContext) -> u32 {
         0
     }
+
+    /// animations looked up by the action itself, besides the one it has been given in the data
+    fn required_animations(&self) -> &'static [AnimationType] {
+        &[]
+    }
 }
@@ -4,10 +4,11 @@ use crate::ai::working_memory::{Event, FactQuery, FactQueryCheck};
 use crate::ai::world_state::WorldState;
 use crate::animations::animation_data::AnimationType;
 use crate::goap_actions::action_component::ActionComponent;
+use crate::goap_actions::action_parameter::ActionParameter;
 use crate::goap_actions::action_types::{
     ActionBehavior, AgentActionPlanContext, AgentActionWorldContext,
 };
-use crate::goap_actions::utils::action_set_animate_state;
+use crate::goap_actions::utils::{action_set_animate_state, action_set_attack_state};
 use crate::thinker_states::animate::AnimateState;
 use godot::prelude::godot_print;
 use serde::{Deserialize, Serialize};
@@ -21,11 +22,17 @@ impl ActionBehavior for Animate {
         inner: &ActionComponent,
         mut action_arguments: AgentActionWorldContext,
     ) {
-        action_set_animate_state(inner, &mut action_arguments);
+        // animate actions bound to an attack play its steps
+        if inner.parameter == Some(ActionParameter::Attack) {
+            action_set_attack_state(inner, &mut action_arguments);
+        } else {
+            action_set_animate_state(inner, &mut action_arguments);
+        }
     }
 
     fn finish(&self, action_arguments: AgentActionWorldContext) {
         action_arguments.blackboard.animation_completed = false;
+        action_arguments.blackboard.attack_in_progress = false;
     }
 
     fn is_action_complete(&self, action_arguments: &AgentActionWorldContext) -> bool {
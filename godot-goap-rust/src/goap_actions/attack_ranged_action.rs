@@ -1,7 +1,7 @@
 use crate::ai::blackboard::SpeedMod;
 use crate::goap_actions::action_component::ActionComponent;
 use crate::goap_actions::action_types::{ActionBehavior, AgentActionWorldContext};
-use crate::goap_actions::utils::action_set_animate_state;
+use crate::goap_actions::utils::action_set_attack_state;
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
@@ -13,7 +13,7 @@ impl ActionBehavior for RangedAttack {
         inner: &ActionComponent,
         mut action_arguments: AgentActionWorldContext,
     ) {
-        action_set_animate_state(inner, &mut action_arguments);
+        action_set_attack_state(inner, &mut action_arguments);
     }
 
     fn finish(&self, action_arguments: AgentActionWorldContext) {
@@ -21,6 +21,7 @@ impl ActionBehavior for RangedAttack {
         action_arguments.blackboard.invalidate_target = true;
         action_arguments.blackboard.rotation_target = None;
         action_arguments.blackboard.animation_completed = false;
+        action_arguments.blackboard.attack_in_progress = false;
     }
 
     fn is_action_complete(&self, action_arguments: &AgentActionWorldContext) -> bool {
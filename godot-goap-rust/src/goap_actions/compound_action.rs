@@ -0,0 +1,206 @@
+use crate::ai::blackboard::PlannedAction;
+use crate::ai::world_state::WorldState;
+use crate::goap_actions::action_component::ActionComponent;
+use crate::goap_actions::action_types::{Action, ActionBehavior, AgentActionWorldContext};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// planned as a single step, expanded into its steps when it is about to be performed.
+/// Steps are names of the other, non-compound actions from the same action set.
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+pub struct Compound {
+    pub steps: Vec<String>,
+    /// indexes of the steps in the action set, resolved once the actions are loaded
+    #[serde(skip)]
+    pub step_indices: Vec<usize>,
+}
+
+impl ActionBehavior for Compound {
+    fn execute_action(&self, _inner: &ActionComponent, _action_arguments: AgentActionWorldContext) {
+    }
+
+    fn finish(&self, _action_arguments: AgentActionWorldContext) {}
+
+    fn is_action_complete(&self, _action_arguments: &AgentActionWorldContext) -> bool {
+        true
+    }
+}
+
+/// resolves steps of all the compound actions into indexes of the actions they name.
+/// Fails if some step names no action or another compound action.
+pub fn resolve_compound_steps(actions: &mut [ActionComponent]) -> Result<(), String> {
+    for i in 0..actions.len() {
+        let Action::Compound(compound) = &actions[i].action_type else {
+            continue;
+        };
+        let mut step_indices = Vec::with_capacity(compound.steps.len());
+        for step in compound.steps.iter() {
+            let Some(index) = actions.iter().position(|action| action.name == *step) else {
+                return Err(format!(
+                    "compound action {}: no step named {}",
+                    actions[i].name, step
+                ));
+            };
+            if let Action::Compound(_) = actions[index].action_type {
+                return Err(format!(
+                    "compound action {}: step {} is a compound action",
+                    actions[i].name, step
+                ));
+            }
+            step_indices.push(index);
+        }
+        if let Action::Compound(compound) = &mut actions[i].action_type {
+            compound.step_indices = step_indices;
+        }
+    }
+    Ok(())
+}
+
+/// replaces the compound action at the front of the plan with its steps.
+/// Steps are never compound actions themselves, so a single expansion is enough.
+pub fn expand_compound_action(actions: &[ActionComponent], plan: &mut VecDeque<PlannedAction>) {
+    let Some(planned) = plan.front().copied() else {
+        return;
+    };
+    let Action::Compound(compound) = &actions[planned.index].action_type else {
+        return;
+    };
+    plan.pop_front();
+    // steps share the binding of the compound action
+    for index in compound.step_indices.iter().rev() {
+        plan.push_front(PlannedAction {
+            index: *index,
+            binding: planned.binding,
+            parent: Some(planned.index),
+        });
+    }
+}
+
+/// state expected once the current action of the plan is completed, used to check the next action against it.
+/// Steps of a compound action have been planned as a whole, so they aren't checked against each other – returns None
+/// between them – and the effects of the compound action are expected once its last step is completed.
+pub fn expected_state_after_current(
+    actions: &[ActionComponent],
+    world_state: &WorldState,
+    plan: &VecDeque<PlannedAction>,
+) -> Option<WorldState> {
+    let current = plan.front()?;
+    let next = plan.get(1);
+    if current.parent.is_some() && next.is_some_and(|next| next.parent == current.parent) {
+        return None;
+    }
+    let mut expected_state = world_state.clone();
+    expected_state.apply_world_state(&actions[current.index].effects);
+    if let Some(parent) = current.parent {
+        expected_state.apply_world_state(&actions[parent].effects);
+    }
+    Some(expected_state)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::ai::planner::ActionBinding;
+
+    fn actions(compound_steps: &str) -> Vec<ActionComponent> {
+        let data = format!(
+            r#"[
+                (name: "Arm", cost: 1, preconditions: {{}}, effects: {{}}, animation: AttackPrepare, action_type: ArmWeapon(), is_plannable: false),
+                (name: "Fire", cost: 1, preconditions: {{}}, effects: {{}}, animation: Attack, action_type: RangedAttack(), is_plannable: false),
+                (name: "Arm & fire", cost: 2, preconditions: {{}}, effects: {{IsTargetDead: Truth(true)}}, animation: Attack, action_type: Compound(steps: {})),
+                (name: "Release", cost: 1, preconditions: {{IsTargetDead: Truth(true)}}, effects: {{}}, animation: AttackPrepare, action_type: ReleaseWeapon()),
+            ]"#,
+            compound_steps
+        );
+        ron::from_str(&data).unwrap()
+    }
+
+    #[test]
+    fn test_resolving_steps() {
+        let mut valid = actions(r#"["Arm", "Fire"]"#);
+        assert!(resolve_compound_steps(&mut valid).is_ok());
+        let Action::Compound(compound) = &valid[2].action_type else {
+            panic!("not a compound action");
+        };
+        assert_eq!(compound.step_indices, vec![0, 1]);
+
+        let mut unknown_step = actions(r#"["Arm", "Reload"]"#);
+        assert!(resolve_compound_steps(&mut unknown_step).is_err());
+        // compound naming itself would be expanded forever
+        let mut nested = actions(r#"["Arm", "Arm & fire"]"#);
+        assert!(resolve_compound_steps(&mut nested).is_err());
+    }
+
+    #[test]
+    fn test_expanding_compound_action() {
+        let mut actions = actions(r#"["Arm", "Fire"]"#);
+        resolve_compound_steps(&mut actions).unwrap();
+        let binding = Some(ActionBinding::Attack(0));
+        let mut plan = VecDeque::from([
+            PlannedAction {
+                index: 2,
+                binding,
+                parent: None,
+            },
+            PlannedAction {
+                index: 1,
+                binding: None,
+                parent: None,
+            },
+        ]);
+        expand_compound_action(&actions, &mut plan);
+        let expanded: Vec<(usize, Option<ActionBinding>)> = plan
+            .iter()
+            .map(|planned| (planned.index, planned.binding))
+            .collect();
+        assert_eq!(expanded, vec![(0, binding), (1, binding), (1, None)]);
+        let parents: Vec<Option<usize>> = plan.iter().map(|planned| planned.parent).collect();
+        assert_eq!(parents, vec![Some(2), Some(2), None]);
+
+        // only the front of the plan is expanded
+        expand_compound_action(&actions, &mut plan);
+        assert_eq!(plan.len(), 3);
+    }
+
+    #[test]
+    fn test_expected_state_after_compound_action() {
+        let mut actions = actions(r#"["Arm", "Fire"]"#);
+        resolve_compound_steps(&mut actions).unwrap();
+        let release = &actions[3];
+        let mut plan = VecDeque::from([
+            PlannedAction {
+                index: 2,
+                binding: None,
+                parent: None,
+            },
+            PlannedAction {
+                index: 3,
+                binding: None,
+                parent: None,
+            },
+        ]);
+        expand_compound_action(&actions, &mut plan);
+        let world_state = WorldState::default();
+
+        // steps of the compound action aren't checked against each other
+        assert!(expected_state_after_current(&actions, &world_state, &plan).is_none());
+        plan.pop_front();
+        // release depends on the effects of the whole compound action, not of its last step
+        let expected_state = expected_state_after_current(&actions, &world_state, &plan).unwrap();
+        assert_eq!(
+            release
+                .preconditions
+                .count_unsatisfied_world_state_props(&expected_state),
+            0
+        );
+        // the last step alone doesn't kill the target
+        plan[0].parent = None;
+        let expected_state = expected_state_after_current(&actions, &world_state, &plan).unwrap();
+        assert_eq!(
+            release
+                .preconditions
+                .count_unsatisfied_world_state_props(&expected_state),
+            1
+        );
+    }
+}
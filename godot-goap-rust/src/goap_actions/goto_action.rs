@@ -77,12 +77,17 @@ impl ActionBehavior for GoTo {
         }
         true
     }
+
+    fn required_animations(&self) -> &'static [AnimationType] {
+        &[AnimationType::Walk]
+    }
 }
 
 fn get_destination(target: &NavigationTarget) -> Destination {
     match target {
         NavigationTarget::PatrolPoint(ainode_id, pos) => Destination::Position(*pos),
         NavigationTarget::Character(instance_id) => Destination::Character(*instance_id),
+        NavigationTarget::Position(pos) => Destination::Position(*pos),
     }
 }
 
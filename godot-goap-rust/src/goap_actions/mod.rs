@@ -1,15 +1,19 @@
 pub mod action_component;
+pub mod action_parameter;
 pub mod action_types;
 mod aim_action;
 mod animate_action;
 mod arm_weapon_action;
 mod attack_ranged_action;
+pub mod compound_action;
 mod deploy_weapon_action;
 mod draw_weapon_action;
 mod goto_action;
 mod patrol_action;
 mod recover_from_attack_action;
 mod release_weapon_action;
+mod search_action;
+mod use_smart_object_action;
 mod utils;
 
 // rust doesn't allow partial borrows in the Context of the struct – therefore we are creating the proper view using this macro.
@@ -17,11 +21,13 @@ mod utils;
 macro_rules! action_arguments {
     ($thinker: ident) => {{
         $crate::goap_actions::action_types::AgentActionWorldContext {
+            id: &$thinker.id,
             working_memory: $thinker.working_memory,
             current_world_state: $thinker.world_state,
             blackboard: $thinker.blackboard,
             navigation_map_rid: $thinker.navigation_map_rid.clone(),
             animations: &$thinker.animations,
+            attacks: &$thinker.attacks,
             ai_nodes: $thinker.ai_nodes,
         }
     }};
@@ -71,4 +71,8 @@ impl ActionBehavior for Patrol {
     fn check_procedural_preconditions(&self, action_arguments: &AgentActionPlanContext) -> bool {
         true
     }
+
+    fn required_animations(&self) -> &'static [AnimationType] {
+        &[AnimationType::Patrol]
+    }
 }
@@ -0,0 +1,65 @@
+use crate::ai::blackboard::SpeedMod;
+use crate::ai::world_state::{WSProperty, WorldStateProperty};
+use crate::animations::animation_data::AnimationType;
+use crate::goap_actions::action_component::ActionComponent;
+use crate::goap_actions::action_types::{
+    ActionBehavior, AgentActionPlanContext, AgentActionWorldContext,
+};
+use crate::targeting::target::AITarget;
+use crate::thinker_states::search::SearchState;
+use serde::{Deserialize, Serialize};
+
+/// searches the area around the target position – e.g. the last known position of the enemy
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct Search {
+    /// number of seconds spent on searching
+    pub duration: f64,
+    /// radius of the searched area
+    pub radius: f32,
+}
+
+impl Eq for Search {}
+
+impl ActionBehavior for Search {
+    fn execute_action(&self, _inner: &ActionComponent, action_arguments: AgentActionWorldContext) {
+        let Some(center) = action_arguments
+            .blackboard
+            .target
+            .as_ref()
+            .and_then(AITarget::get_target_pos)
+        else {
+            // nothing to search
+            action_arguments.current_world_state[WorldStateProperty::IsAreaSurveyed] =
+                Some(WSProperty::Truth(true));
+            return;
+        };
+        action_arguments.current_world_state[WorldStateProperty::IsAreaSurveyed] =
+            Some(WSProperty::Truth(false));
+        action_arguments.blackboard.walk_speed = SpeedMod::Slow;
+        let anim = &action_arguments.animations[AnimationType::Walk];
+        let ends_at = action_arguments.working_memory.now() + self.duration;
+        let new_state =
+            SearchState::new_boxed(anim.tree_name.clone(), center, self.radius, ends_at);
+        action_arguments.blackboard.new_state = Some(new_state);
+    }
+
+    fn finish(&self, action_arguments: AgentActionWorldContext) {
+        action_arguments.blackboard.rotation_target = None;
+    }
+
+    fn is_action_complete(&self, action_arguments: &AgentActionWorldContext) -> bool {
+        action_arguments.current_world_state[WorldStateProperty::IsAreaSurveyed]
+            == Some(WSProperty::Truth(true))
+    }
+
+    fn check_procedural_preconditions(&self, action_arguments: &AgentActionPlanContext) -> bool {
+        matches!(
+            action_arguments.blackboard.target,
+            Some(AITarget::Disturbance(_) | AITarget::Interest(_))
+        )
+    }
+
+    fn required_animations(&self) -> &'static [AnimationType] {
+        &[AnimationType::Walk]
+    }
+}
@@ -0,0 +1,94 @@
+use crate::ai::planner::ActionBinding;
+use crate::ai::working_memory::{FactQuery, FactQueryCheck, Node, WMProperty};
+use crate::ai::world_state::{NodeTypeEnum, WSProperty, WorldStateProperty};
+use crate::ai_nodes::ai_node::AINode;
+use crate::animations::animation_data::AnimationType;
+use crate::goap_actions::action_component::ActionComponent;
+use crate::goap_actions::action_types::{
+    ActionBehavior, AgentActionPlanContext, AgentActionWorldContext,
+};
+use crate::thinker_states::use_ai_node::UseAINodeState;
+use serde::{Deserialize, Serialize};
+
+/// locks the SmartObject node bound by the planner, goes there, plays its animation & fires its reaction
+#[derive(Debug, Clone, Deserialize, Serialize, Eq, PartialEq)]
+pub struct UseSmartObject;
+
+impl ActionBehavior for UseSmartObject {
+    fn execute_action(&self, _inner: &ActionComponent, action_arguments: AgentActionWorldContext) {
+        action_arguments.current_world_state[WorldStateProperty::AtNodeType] = None;
+        let Some(ActionBinding::Node(ainode_id)) = action_arguments.blackboard.current_binding()
+        else {
+            // action must be bound to some smart object
+            action_arguments.blackboard.invalidate_plan = true;
+            return;
+        };
+        let Ok(ainodes_guard) = action_arguments
+            .ai_nodes
+            .as_ref()
+            .expect("no ainodes")
+            .read()
+        else {
+            panic!("rwlock failed!")
+        };
+        let Some(AINode::SmartObject { base, interaction }) = ainodes_guard.get(&ainode_id) else {
+            action_arguments.blackboard.invalidate_plan = true;
+            return;
+        };
+        // someone else has been faster – forget the node, so the next plan won't be bound to it again
+        if !base.try_lock(*action_arguments.id) {
+            let fact = WMProperty::Node(Node::SmartObject {
+                ainode_id,
+                position: base.position.into(),
+            });
+            action_arguments
+                .working_memory
+                .mark_as_invalid(FactQuery::with_check(FactQueryCheck::Match(fact)));
+            action_arguments.blackboard.invalidate_plan = true;
+            return;
+        }
+        action_arguments.blackboard.current_locked_node = Some(ainode_id);
+        let anim = &action_arguments.animations[AnimationType::Walk];
+        let new_state = UseAINodeState::new_boxed(
+            anim.tree_name.clone(),
+            base.base_id,
+            base.position,
+            interaction.clone(),
+        );
+        action_arguments.blackboard.new_state = Some(new_state);
+    }
+
+    fn finish(&self, action_arguments: AgentActionWorldContext) {
+        action_arguments.blackboard.rotation_target = None;
+        action_arguments.blackboard.animation_completed = false;
+        let Some(ainode_id) = action_arguments.blackboard.current_locked_node else {
+            return;
+        };
+        let Ok(ainodes_guard) = action_arguments
+            .ai_nodes
+            .as_ref()
+            .expect("no ainodes")
+            .read()
+        else {
+            panic!("rwlock failed!")
+        };
+        let Some(ainode @ AINode::SmartObject { .. }) = ainodes_guard.get(&ainode_id) else {
+            return;
+        };
+        ainode.base().unlock(*action_arguments.id);
+        action_arguments.blackboard.current_locked_node = None;
+    }
+
+    fn is_action_complete(&self, action_arguments: &AgentActionWorldContext) -> bool {
+        action_arguments.current_world_state[WorldStateProperty::AtNodeType]
+            == Some(WSProperty::Node(NodeTypeEnum::SmartObject))
+    }
+
+    fn check_procedural_preconditions(&self, action_arguments: &AgentActionPlanContext) -> bool {
+        action_arguments.blackboard.target.is_none()
+    }
+
+    fn required_animations(&self) -> &'static [AnimationType] {
+        &[AnimationType::Walk]
+    }
+}
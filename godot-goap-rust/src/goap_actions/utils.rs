@@ -1,6 +1,7 @@
 use crate::goap_actions::action_component::ActionComponent;
 use crate::goap_actions::action_types::AgentActionWorldContext;
 use crate::thinker_states::animate::AnimateState;
+use crate::thinker_states::attack::AttackState;
 
 pub fn action_set_animate_state(
     inner: &ActionComponent,
@@ -20,3 +21,21 @@ pub fn action_set_animate_state(
     );
     action_arguments.blackboard.new_state = Some(new_state);
 }
+
+/// plays the steps of the chosen attack. Falls back to the action animation if no attack has been chosen.
+pub fn action_set_attack_state(
+    inner: &ActionComponent,
+    action_arguments: &mut AgentActionWorldContext,
+) {
+    let Some(attack) = action_arguments
+        .blackboard
+        .chosen_attack_idx
+        .and_then(|idx| action_arguments.attacks.get(idx))
+    else {
+        action_set_animate_state(inner, action_arguments);
+        return;
+    };
+    action_arguments.blackboard.animation_completed = false;
+    action_arguments.blackboard.attack_in_progress = true;
+    action_arguments.blackboard.new_state = Some(AttackState::new_boxed(attack.steps.clone()));
+}
@@ -1,4 +1,6 @@
 use crate::ai::blackboard::{NavigationTarget, SpeedMod};
+use crate::ai::squad::SquadRole;
+use crate::ai::working_memory::Task;
 use crate::goap_goals::goal_component::GoalComponent;
 use crate::goap_goals::goal_types::{AgentGoalWorldContext, GoalBehaviour};
 use crate::targeting::target::AITarget;
@@ -12,7 +14,9 @@ impl GoalBehaviour for ChaseEnemyGoal {
         let Some(AITarget::Character(..)) = agent_world_context.blackboard.target else {
             return false;
         };
-        true
+        // squad members told to hold or to cover the others don't rush the target
+        agent_world_context.blackboard.squad_role != Some(SquadRole::Hold)
+            && agent_world_context.blackboard.task != Some(Task::Cover)
     }
 
     fn activate(
@@ -24,7 +28,11 @@ impl GoalBehaviour for ChaseEnemyGoal {
         else {
             return false;
         };
-        agent_world_context.blackboard.walk_speed = SpeedMod::Normal;
+        // flankers hurry while the others keep the target busy
+        agent_world_context.blackboard.walk_speed = match agent_world_context.blackboard.task {
+            Some(Task::Advance) => SpeedMod::Fast,
+            _ => SpeedMod::Normal,
+        };
         agent_world_context.blackboard.navigation_target = Some(NavigationTarget::Character(*i));
         true
     }
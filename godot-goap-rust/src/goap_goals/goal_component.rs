@@ -1,5 +1,6 @@
 use crate::ai::world_state::{NodeTypeEnum, WorldState};
-use crate::goap_goals::goal_types::GoalType;
+use crate::goap_goals::goal_types::{AgentGoalWorldContext, GoalBehaviour, GoalType};
+use crate::goap_goals::utility::{combine_scores, Consideration, GoalScore};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -12,6 +13,26 @@ pub struct GoalComponent {
     pub goal_type: GoalType,
     pub desired_state: WorldState,
     pub required_state: WorldState,
+    /// scale the relevance of the goal, none – relevance is constant
+    #[serde(default)]
+    pub considerations: Vec<Consideration>,
+}
+
+impl GoalComponent {
+    /// relevance of the goal scaled by the combined score of its considerations
+    pub fn relevance(&self, index: usize, context: &AgentGoalWorldContext) -> GoalScore {
+        let relevance = self.goal_type.calculate_goal_relevance(self, context);
+        let considerations: Vec<f32> = self
+            .considerations
+            .iter()
+            .map(|consideration| consideration.score(context))
+            .collect();
+        GoalScore {
+            goal: index,
+            relevance: (relevance as f32 * combine_scores(&considerations)).round() as u32,
+            considerations,
+        }
+    }
 }
 
 fn is_interruptible_default() -> bool {
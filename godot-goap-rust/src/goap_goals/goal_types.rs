@@ -1,14 +1,17 @@
 use crate::ai::blackboard::Blackboard;
-use crate::ai::working_memory::WorkingMemory;
+use crate::ai::working_memory::{WorkingMemory, WorkingMemoryFactTypeKey};
 use crate::ai::world_state::WorldState;
 use crate::ai_nodes::ai_node::AINode;
 use crate::goap_goals::basic_goal::BasicGoal;
 use crate::goap_goals::chase_enemy_goal::ChaseEnemyGoal;
 use crate::goap_goals::dodge_goal::DodgeGoal;
 use crate::goap_goals::goal_component::GoalComponent;
+use crate::goap_goals::investigate_goal::InvestigateLastKnownPositionGoal;
 use crate::goap_goals::kill_enemy_goal::KillEnemyGoal;
 use crate::goap_goals::patrol_goal::PatrolGoal;
 use crate::goap_goals::satisfy_desire_by_animation_goal::SatisfyDesireByPlayingAnimationGoal;
+use crate::goap_goals::take_cover_goal::TakeCoverGoal;
+use crate::goap_goals::use_smart_object_goal::UseSmartObjectGoal;
 use enum_dispatch::enum_dispatch;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -31,9 +34,12 @@ pub enum GoalType {
     BasicGoal,
     ChaseEnemyGoal,
     DodgeGoal,
+    InvestigateLastKnownPositionGoal,
     KillEnemyGoal,
     PatrolGoal,
     SatisfyDesireByPlayingAnimationGoal,
+    TakeCoverGoal,
+    UseSmartObjectGoal,
 }
 
 #[enum_dispatch(GoalType)]
@@ -79,4 +85,9 @@ pub trait GoalBehaviour {
     }
 
     fn deactivate(&self, _goal: &GoalComponent, _agent_world_context: &mut AgentGoalWorldContext) {}
+
+    /// types of the facts the goal depends on, used to validate the data set
+    fn read_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[]
+    }
 }
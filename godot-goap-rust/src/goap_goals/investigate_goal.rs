@@ -0,0 +1,73 @@
+use crate::ai::blackboard::{NavigationTarget, SpeedMod};
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, Knowledge, WMProperty, WorkingMemoryFactTypeKey,
+};
+use crate::ai::world_state::{WSProperty, WorldStateProperty};
+use crate::goap_goals::goal_component::GoalComponent;
+use crate::goap_goals::goal_types::{AgentGoalWorldContext, GoalBehaviour};
+use crate::targeting::target::AITarget;
+use serde::{Deserialize, Serialize};
+
+/// goes to the last known position of the lost target & searches the area around it
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct InvestigateLastKnownPositionGoal;
+
+impl GoalBehaviour for InvestigateLastKnownPositionGoal {
+    fn read_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Knowledge]
+    }
+
+    fn is_valid(&self, _goal: &GoalComponent, agent_world_context: &AgentGoalWorldContext) -> bool {
+        matches!(
+            agent_world_context.blackboard.target,
+            Some(AITarget::Disturbance(_) | AITarget::Interest(_))
+        )
+    }
+
+    fn activate(
+        &self,
+        _goal: &GoalComponent,
+        agent_world_context: &mut AgentGoalWorldContext,
+    ) -> bool {
+        let Some(position) = agent_world_context
+            .blackboard
+            .target
+            .as_ref()
+            .and_then(AITarget::get_target_pos)
+        else {
+            return false;
+        };
+        agent_world_context.blackboard.walk_speed = SpeedMod::Normal;
+        agent_world_context.blackboard.navigation_target =
+            Some(NavigationTarget::Position(position));
+        true
+    }
+
+    /// forgets the last known position once it has been searched
+    fn deactivate(&self, _goal: &GoalComponent, agent_world_context: &mut AgentGoalWorldContext) {
+        agent_world_context.blackboard.navigation_target = None;
+        let is_surveyed = agent_world_context.current_world_state
+            [WorldStateProperty::IsAreaSurveyed]
+            == Some(WSProperty::Truth(true));
+        agent_world_context.current_world_state[WorldStateProperty::IsAreaSurveyed] = None;
+        if !is_surveyed {
+            return;
+        }
+        // there might be other disturbances still worth investigating
+        let Some(position) = agent_world_context
+            .blackboard
+            .target
+            .as_ref()
+            .and_then(AITarget::get_target_pos)
+        else {
+            return;
+        };
+        let fact_query = FactQuery::with_check(FactQueryCheck::Match(WMProperty::Knowledge(
+            Knowledge::LastTargetPosition(position.into()),
+        )));
+        agent_world_context
+            .working_memory
+            .mark_as_invalid(fact_query);
+        agent_world_context.blackboard.invalidate_target = true;
+    }
+}
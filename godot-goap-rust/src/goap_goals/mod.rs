@@ -4,10 +4,14 @@ mod dodge_goal;
 mod execute_attack_goal;
 pub mod goal_component;
 pub mod goal_types;
+mod investigate_goal;
 mod kill_enemy_goal;
 mod patrol_goal;
 mod react_to_damage_goal;
 mod satisfy_desire_by_animation_goal;
+mod take_cover_goal;
+mod use_smart_object_goal;
+pub mod utility;
 
 // rust doesn't allow partial borrows in the Context of the struct – therefore we are creating the proper view using this macro.
 #[macro_export]
@@ -1,5 +1,7 @@
 use crate::ai::blackboard::{NavigationTarget, SpeedMod};
-use crate::ai::working_memory::{FactQuery, FactQueryCheck, Node, WMNodeType, WMProperty};
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, Node, WMNodeType, WMProperty, WorkingMemoryFactTypeKey,
+};
 use crate::ai_nodes::ai_node::AINode;
 use crate::goap_goals::goal_component::GoalComponent;
 use crate::goap_goals::goal_types::{AgentGoalWorldContext, GoalBehaviour};
@@ -10,6 +12,10 @@ use std::sync::atomic::Ordering;
 pub struct PatrolGoal;
 
 impl GoalBehaviour for PatrolGoal {
+    fn read_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Node]
+    }
+
     fn is_valid(&self, _goal: &GoalComponent, agent_world_context: &AgentGoalWorldContext) -> bool {
         if agent_world_context.blackboard.current_locked_node.is_some() {
             return true;
@@ -62,8 +68,9 @@ impl GoalBehaviour for PatrolGoal {
                 base.status
                     .store(*agent_world_context.id, Ordering::Release);
                 agent_world_context.blackboard.current_locked_node = Some(*ainode_id);
-                agent_world_context.blackboard.navigation_target =
-                    Some(NavigationTarget::PatrolPoint(*ainode_id, *position));
+                agent_world_context.blackboard.navigation_target = Some(
+                    NavigationTarget::PatrolPoint(*ainode_id, (*position).into()),
+                );
                 agent_world_context.blackboard.walk_speed = SpeedMod::Slow;
                 agent_world_context.blackboard.rotation_speed = SpeedMod::Slow;
                 return true;
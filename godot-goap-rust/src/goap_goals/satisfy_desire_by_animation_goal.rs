@@ -1,4 +1,6 @@
-use crate::ai::working_memory::{FactQuery, FactQueryCheck, WMDesireType};
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, WMDesireType, WorkingMemoryFactTypeKey,
+};
 use crate::animations::animation_data::AnimationType;
 use crate::goap_goals::goal_component::GoalComponent;
 use crate::goap_goals::goal_types::{AgentGoalWorldContext, GoalBehaviour};
@@ -11,6 +13,10 @@ pub struct SatisfyDesireByPlayingAnimationGoal {
 }
 
 impl GoalBehaviour for SatisfyDesireByPlayingAnimationGoal {
+    fn read_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Desire]
+    }
+
     fn is_valid(&self, _goal: &GoalComponent, agent_world_context: &AgentGoalWorldContext) -> bool {
         let fact_query = FactQuery::with_check(FactQueryCheck::Desire(self.desire_type));
         if agent_world_context
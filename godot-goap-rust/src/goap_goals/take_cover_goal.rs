@@ -0,0 +1,128 @@
+use crate::ai::blackboard::{NavigationTarget, SpeedMod};
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, Node, WMNodeType, WMProperty, WorkingMemoryFactTypeKey,
+};
+use crate::ai::world_state::{CoverStatusType, WSProperty, WorldStateProperty};
+use crate::goap_goals::goal_component::GoalComponent;
+use crate::goap_goals::goal_types::{AgentGoalWorldContext, GoalBehaviour};
+use serde::{Deserialize, Serialize};
+
+/// locks the best scored node of given types & goes there to get covered from the current target
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct TakeCoverGoal {
+    #[serde(default = "node_types_default")]
+    node_types: Vec<WMNodeType>,
+}
+
+fn node_types_default() -> Vec<WMNodeType> {
+    vec![WMNodeType::Cover, WMNodeType::Hide, WMNodeType::Ambush]
+}
+
+impl Default for TakeCoverGoal {
+    fn default() -> Self {
+        TakeCoverGoal {
+            node_types: node_types_default(),
+        }
+    }
+}
+
+impl TakeCoverGoal {
+    /// best scored node among the known ones
+    fn find_best_node(&self, agent_world_context: &AgentGoalWorldContext) -> Option<Node> {
+        self.node_types
+            .iter()
+            .filter_map(|node_type| {
+                let fact_query = FactQuery::with_check(FactQueryCheck::Node(*node_type));
+                let fact = agent_world_context
+                    .working_memory
+                    .find_fact_with_max_confidence(fact_query)?;
+                let WMProperty::Node(node) = &fact.f_type else {
+                    return None;
+                };
+                Some((node, fact.confidence))
+            })
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(node, _score)| node.clone())
+    }
+}
+
+impl GoalBehaviour for TakeCoverGoal {
+    fn read_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Node]
+    }
+
+    fn is_valid(&self, _goal: &GoalComponent, agent_world_context: &AgentGoalWorldContext) -> bool {
+        agent_world_context.blackboard.target.is_some()
+            && self.find_best_node(agent_world_context).is_some()
+    }
+
+    /// lock the best node & set it as the navigation target
+    fn activate(
+        &self,
+        _goal: &GoalComponent,
+        agent_world_context: &mut AgentGoalWorldContext,
+    ) -> bool {
+        let Some(node) = self.find_best_node(agent_world_context) else {
+            return false;
+        };
+        let ainode_id = node.ainode_id();
+        let Ok(ainodes_guard) = agent_world_context
+            .ai_nodes
+            .as_mut()
+            .expect("no ainodes")
+            .read()
+        else {
+            panic!("rwlock failed!")
+        };
+        let is_locked = ainodes_guard
+            .get(&ainode_id)
+            .map(|ainode| ainode.base().try_lock(*agent_world_context.id))
+            .unwrap_or(false);
+        // thinker might still hold the node it has been covered by before
+        if let Some(previous_id) = agent_world_context.blackboard.current_locked_node {
+            if is_locked && previous_id != ainode_id {
+                if let Some(previous) = ainodes_guard.get(&previous_id) {
+                    previous.base().unlock(*agent_world_context.id);
+                }
+            }
+        }
+        drop(ainodes_guard);
+        if !is_locked {
+            let fact_query = FactQuery::with_check(FactQueryCheck::Match(WMProperty::Node(node)));
+            agent_world_context
+                .working_memory
+                .mark_as_invalid(fact_query);
+            return false;
+        }
+        agent_world_context.blackboard.current_locked_node = Some(ainode_id);
+        agent_world_context.blackboard.navigation_target =
+            Some(NavigationTarget::Position(node.position().into()));
+        agent_world_context.blackboard.walk_speed = SpeedMod::Fast;
+        agent_world_context.blackboard.rotation_speed = SpeedMod::Fast;
+        true
+    }
+
+    /// free the locked node, unless the thinker stays in cover – in such case it is freed by the CoverNodeSensor
+    fn deactivate(&self, _goal: &GoalComponent, agent_world_context: &mut AgentGoalWorldContext) {
+        agent_world_context.blackboard.navigation_target = None;
+        let is_covered = agent_world_context.current_world_state[WorldStateProperty::CoverStatus]
+            == Some(WSProperty::CoverStatus(CoverStatusType::Covered));
+        if is_covered && agent_world_context.blackboard.target.is_some() {
+            return;
+        }
+        let Some(ainode_id) = agent_world_context.blackboard.current_locked_node.take() else {
+            return;
+        };
+        let Ok(ainodes_guard) = agent_world_context
+            .ai_nodes
+            .as_mut()
+            .expect("no ainodes")
+            .read()
+        else {
+            panic!("rwlock failed!")
+        };
+        if let Some(ainode) = ainodes_guard.get(&ainode_id) {
+            ainode.base().unlock(*agent_world_context.id);
+        }
+    }
+}
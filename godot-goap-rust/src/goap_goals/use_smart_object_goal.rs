@@ -0,0 +1,64 @@
+use crate::ai::blackboard::SpeedMod;
+use crate::ai::working_memory::{FactQuery, FactQueryCheck, WMNodeType, WorkingMemoryFactTypeKey};
+use crate::ai::world_state::WorldStateProperty;
+use crate::ai_nodes::ai_node::AINode;
+use crate::goap_goals::goal_component::GoalComponent;
+use crate::goap_goals::goal_types::{AgentGoalWorldContext, GoalBehaviour};
+use serde::{Deserialize, Serialize};
+
+/// lets the idle thinker perform the activities advertised by the nearby smart objects
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+pub struct UseSmartObjectGoal;
+
+impl UseSmartObjectGoal {
+    fn is_using_smart_object(agent_world_context: &AgentGoalWorldContext) -> bool {
+        let Some(ainode_id) = agent_world_context.blackboard.current_locked_node else {
+            return false;
+        };
+        let Some(ainodes) = agent_world_context.ai_nodes.as_ref() else {
+            return false;
+        };
+        let Ok(ainodes_guard) = ainodes.read() else {
+            panic!("rwlock failed!")
+        };
+        matches!(
+            ainodes_guard.get(&ainode_id),
+            Some(AINode::SmartObject { .. })
+        )
+    }
+}
+
+impl GoalBehaviour for UseSmartObjectGoal {
+    fn read_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Node]
+    }
+
+    fn is_valid(&self, _goal: &GoalComponent, agent_world_context: &AgentGoalWorldContext) -> bool {
+        if agent_world_context.blackboard.target.is_some() {
+            return false;
+        }
+        if Self::is_using_smart_object(agent_world_context) {
+            return true;
+        }
+        let fact_query = FactQuery::with_check(FactQueryCheck::Node(WMNodeType::SmartObject));
+        agent_world_context
+            .working_memory
+            .find_fact(fact_query)
+            .is_some()
+    }
+
+    fn activate(
+        &self,
+        _goal: &GoalComponent,
+        agent_world_context: &mut AgentGoalWorldContext,
+    ) -> bool {
+        agent_world_context.blackboard.walk_speed = SpeedMod::Slow;
+        agent_world_context.blackboard.rotation_speed = SpeedMod::Slow;
+        true
+    }
+
+    /// smart object can be used again once the thinker walks away from it
+    fn deactivate(&self, _goal: &GoalComponent, agent_world_context: &mut AgentGoalWorldContext) {
+        agent_world_context.current_world_state[WorldStateProperty::AtNodeType] = None;
+    }
+}
@@ -0,0 +1,162 @@
+// Utility considerations scale the static priority of a goal.
+// Each consideration maps some input (distance, health...) through a response curve into a score in range of 0-1.
+
+use crate::ai::types::EntityId;
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, Knowledge, WMProperty, WMTaskType, WorkingMemoryFactTypeKey,
+};
+use crate::goap_goals::goal_types::AgentGoalWorldContext;
+use crate::targeting::target::AITarget;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ConsiderationInput {
+    DistanceToTarget,
+    /// fraction of the hit points left
+    Health,
+    /// seconds since the target character has been seen last time
+    TimeSinceTargetSeen,
+    /// highest confidence among the facts of a given type
+    FactConfidence(WorkingMemoryFactTypeKey),
+    /// 1 if the squad has given the thinker a task of a given type, 0 otherwise
+    Task(WMTaskType),
+}
+
+impl ConsiderationInput {
+    fn value(&self, context: &AgentGoalWorldContext) -> f32 {
+        match self {
+            ConsiderationInput::DistanceToTarget => {
+                context.blackboard.distance_to_target.unwrap_or(f32::MAX)
+            }
+            ConsiderationInput::Health => context.blackboard.health.unwrap_or(1.0),
+            ConsiderationInput::TimeSinceTargetSeen => {
+                let Some(AITarget::Character(id, _)) = context.blackboard.target.as_ref() else {
+                    return f32::MAX;
+                };
+                let query = FactQuery::with_check(FactQueryCheck::Match(WMProperty::Knowledge(
+                    Knowledge::Character(EntityId::from(*id), None),
+                )));
+                context
+                    .working_memory
+                    .find_fact(query)
+                    .map(|fact| (context.working_memory.now() - fact.update_time) as f32)
+                    .unwrap_or(f32::MAX)
+            }
+            ConsiderationInput::FactConfidence(fact_type) => context
+                .working_memory
+                .facts_of_type(*fact_type)
+                .map(|fact| fact.confidence)
+                .fold(0.0, f32::max),
+            ConsiderationInput::Task(task_type) => {
+                let has_task = context
+                    .blackboard
+                    .task
+                    .as_ref()
+                    .is_some_and(|task| WMTaskType::from(task) == *task_type);
+                if has_task {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        }
+    }
+}
+
+/// maps normalized input into a score
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub enum ResponseCurve {
+    /// slope * x + offset
+    Linear { slope: f32, offset: f32 },
+    /// slope * x^exponent + offset
+    Polynomial {
+        exponent: f32,
+        slope: f32,
+        offset: f32,
+    },
+    /// s-shaped curve, rising around the midpoint
+    Logistic { steepness: f32, midpoint: f32 },
+    /// 1 at or above the threshold, 0 below
+    Step { threshold: f32 },
+}
+
+impl Default for ResponseCurve {
+    fn default() -> Self {
+        ResponseCurve::Linear {
+            slope: 1.0,
+            offset: 0.0,
+        }
+    }
+}
+
+impl ResponseCurve {
+    pub fn evaluate(&self, x: f32) -> f32 {
+        let y = match self {
+            ResponseCurve::Linear { slope, offset } => slope * x + offset,
+            ResponseCurve::Polynomial {
+                exponent,
+                slope,
+                offset,
+            } => slope * x.powf(*exponent) + offset,
+            ResponseCurve::Logistic {
+                steepness,
+                midpoint,
+            } => 1.0 / (1.0 + (-steepness * (x - midpoint)).exp()),
+            ResponseCurve::Step { threshold } => {
+                if x >= *threshold {
+                    1.0
+                } else {
+                    0.0
+                }
+            }
+        };
+        y.clamp(0.0, 1.0)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Consideration {
+    pub input: ConsiderationInput,
+    /// input values in this range are mapped into 0-1 before being passed to the curve
+    #[serde(default = "range_default")]
+    pub range: (f32, f32),
+    #[serde(default)]
+    pub curve: ResponseCurve,
+}
+
+fn range_default() -> (f32, f32) {
+    (0.0, 1.0)
+}
+
+impl Consideration {
+    pub fn score(&self, context: &AgentGoalWorldContext) -> f32 {
+        let (min, max) = self.range;
+        let x = if max > min {
+            ((self.input.value(context) - min) / (max - min)).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        self.curve.evaluate(x)
+    }
+}
+
+/// relevance of a goal with the scores of all its considerations, kept for debugging
+#[derive(Debug, Clone)]
+pub struct GoalScore {
+    pub goal: usize,
+    pub relevance: u32,
+    pub considerations: Vec<f32>,
+}
+
+/// multiplies the scores, compensating for the number of considerations –
+/// otherwise goals with more considerations would always score lower
+pub fn combine_scores(scores: &[f32]) -> f32 {
+    if scores.is_empty() {
+        return 1.0;
+    }
+    let modification = 1.0 - 1.0 / scores.len() as f32;
+    scores.iter().fold(1.0, |total, score| {
+        let make_up = (1.0 - score) * modification;
+        total * (score + make_up * score)
+    })
+}
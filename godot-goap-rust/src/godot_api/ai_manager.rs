@@ -1,23 +1,34 @@
-use crate::ai::process_plan::{process_plan, ThinkerPlanEvent, ThinkerProcess};
+use crate::ai::ai_stimulus::{AIStimulus, AIStimulusType};
+use crate::ai::data_validation::validate_data_set;
+use crate::ai::plan_cache::PlanCache;
+use crate::ai::process_plan::{
+    process_plan, reset_plan, with_plan_view, PlanBatch, ThinkerPlanEvent, ThinkerProcess,
+};
+use crate::ai::replay::replay;
+use crate::ai::squad::{Squad, SquadCoordinator};
 use crate::ai::thinker::{Thinker, ThinkerShared};
-use crate::ai::working_memory::WMProperty;
+use crate::ai::working_memory::{WMProperty, WorkingMemory};
 use crate::ai_nodes::ai_node::AINode;
 use crate::ai_nodes::godot_ai_node::GodotAINode;
 use crate::animations::animation_data::{AnimationProps, AnimationType, AnimationsData};
+use crate::attacks::attack_data::AttackData;
 use crate::goap_actions::action_component::ActionComponent;
+use crate::goap_actions::compound_action::resolve_compound_steps;
 use crate::goap_goals::goal_component::GoalComponent;
 use crate::godot_api::gamesys::GameSystem;
 use crate::godot_api::godot_thinker::GodotThinker;
 use crate::godot_api::CONNECT_ONE_SHOT;
-use crate::sensors::sensor_types::PollingSensor;
+use crate::sensors::sensor_types::SensorsConfig;
 use crate::thinker_states::process_thinker::process_thinker;
+use crate::thinker_states::types::StateArguments;
 use crate::utils::generate_id::{assign_id, ToCreate};
 use godot::classes::file_access::ModeFlags;
-use godot::classes::{Engine, FileAccess};
+use godot::classes::{Engine, FileAccess, ProjectSettings};
 use godot::prelude::*;
 use rayon::prelude::*;
 use serde::Deserialize;
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::Path;
 use std::sync::mpsc::{Receiver, Sender};
 use std::sync::{mpsc, RwLock};
 use std::sync::{Arc, Mutex};
@@ -29,8 +40,23 @@ pub struct GodotAIManager {
     pub actions: HashMap<GString, Arc<Vec<ActionComponent>>>,
     pub goals: HashMap<GString, Arc<Vec<GoalComponent>>>,
     pub animations: HashMap<GString, Arc<AnimationsData>>,
-    sensors_blueprint: HashMap<GString, Vec<PollingSensor>>,
+    pub attacks: HashMap<GString, Arc<Vec<AttackData>>>,
+    sensors_blueprint: HashMap<GString, SensorsConfig>,
+    /// modification times of the loaded data files, used to detect the changes
+    data_modified_times: HashMap<GString, u64>,
+    /// actions, goals, animations & sensors files of the data sets validated so far
+    validated_data_sets: HashSet<[GString; 4]>,
+    /// reloads changed data files every second – works only in debug builds
+    #[var]
+    pub watch_ai_data: bool,
+    watch_ai_data_delta: f64,
     pub ai_nodes: Arc<RwLock<HashMap<u32, AINode>>>,
+    pub squads: HashMap<GString, Squad>,
+    /// coordinators of the squads by their names – squads not listed there use the default one
+    #[var]
+    pub squads_file: GString,
+    /// plans shared by all the thinkers using the same action sets
+    pub plan_cache: Arc<Mutex<PlanCache>>,
     ainode_id_with_dependencies: VecDeque<(u32, Gd<GodotAINode>)>,
 
     pub thinkers: HashMap<u32, Thinker>,
@@ -39,6 +65,9 @@ pub struct GodotAIManager {
     pub is_initialized: bool,
     pub current_thinker_id: u32,
     pub current_node_id: u32,
+    /// max number of plan searches performed in a single frame. 0 means unlimited.
+    #[var]
+    pub max_replans_per_frame: u32,
     pub sender: Option<Sender<ThinkerPlanEvent>>,
     pub receiver: Option<Receiver<()>>,
     pub thread: Option<thread::JoinHandle<()>>,
@@ -95,27 +124,198 @@ impl GodotAIManager {
     fn get_thinker_debug_data(&self, thinker_id: u32) -> Dictionary {
         let thinker = self.thinkers.get(&thinker_id).unwrap();
         let shared = thinker.shared.lock().unwrap();
-        let current_goal: String = if let Some(current) = shared.blackboard.current_goal {
-            thinker.goals[current].name.clone()
-        } else {
-            "no goal".to_string()
-        };
-        let current_action: String = if let Some(current) = shared.blackboard.current_action() {
-            format!("{:?}", thinker.actions[current])
-        } else {
-            "no action".to_string()
-        };
+        // indices might refer to the old data until the planner resets the reloaded thinkers
+        let current_goal: String = shared
+            .blackboard
+            .current_goal
+            .and_then(|current| thinker.goals.get(current))
+            .map(|goal| goal.name.clone())
+            .unwrap_or_else(|| "no goal".to_string());
+        let current_action: String = shared
+            .blackboard
+            .current_action()
+            .and_then(|current| thinker.actions.get(current))
+            .map(|action| format!("{:?}", action))
+            .unwrap_or_else(|| "no action".to_string());
         let current_world_state = format!("{:?}", shared.world_state);
+        let squad_role = format!("{:?}", shared.blackboard.squad_role);
+        let goal_scores: Array<GString> = shared
+            .blackboard
+            .goal_scores
+            .iter()
+            .filter_map(|score| {
+                let goal = thinker.goals.get(score.goal)?;
+                let considerations: Vec<String> = goal
+                    .considerations
+                    .iter()
+                    .zip(score.considerations.iter())
+                    .map(|(consideration, value)| {
+                        format!("{:?}: {:.2}", consideration.input, value)
+                    })
+                    .collect();
+                Some(GString::from(format!(
+                    "{}: {} [{}]",
+                    goal.name,
+                    score.relevance,
+                    considerations.join(", ")
+                )))
+            })
+            .collect();
+        let (plan_cache_hits, plan_cache_misses) = {
+            let plan_cache = self.plan_cache.lock().unwrap();
+            (plan_cache.hits as i64, plan_cache.misses as i64)
+        };
         dict! {
             "current_world_state": current_world_state,
             "goal": current_goal,
-            "action": current_action
+            "action": current_action,
+            "squad_role": squad_role,
+            "goal_scores": goal_scores,
+            "plan_cache_hits": plan_cache_hits,
+            "plan_cache_misses": plan_cache_misses
+        }
+    }
+
+    /// records inputs & outcomes of all the goal selections and plan searches into a given file
+    #[func]
+    fn start_recording(&mut self, path: GString) {
+        let path = ProjectSettings::singleton()
+            .globalize_path(&path)
+            .to_string();
+        if let Some(sender) = self.sender.as_mut() {
+            let _result = sender.send(ThinkerPlanEvent::StartRecording(path.into()));
+        }
+    }
+
+    #[func]
+    fn stop_recording(&mut self) {
+        if let Some(sender) = self.sender.as_mut() {
+            let _result = sender.send(ThinkerPlanEvent::StopRecording);
         }
     }
 
+    /// feeds recorded inputs through the goal selection & the planner of currently registered thinkers.
+    /// Returns true if all the decisions are the same as the recorded ones.
+    #[func]
+    fn replay_decisions(&self, path: GString) -> bool {
+        let path = ProjectSettings::singleton()
+            .globalize_path(&path)
+            .to_string();
+        match replay(Path::new(&path), &self.thinkers) {
+            Ok((replayed, mismatches)) => {
+                for mismatch in mismatches.iter() {
+                    godot_warn!("replay mismatch – {}", mismatch);
+                }
+                godot_print!(
+                    "replayed {} decisions, {} mismatches",
+                    replayed,
+                    mismatches.len()
+                );
+                mismatches.is_empty()
+            }
+            Err(e) => {
+                godot_error!("couldn't replay {}: {}", path, e);
+                false
+            }
+        }
+    }
+
+    /// re-parses the data files changed since they were loaded & resets plans of the thinkers using them.
+    /// Returns number of reloaded files.
+    #[func]
+    pub fn reload_ai_data(&mut self) -> u32 {
+        let changed: Vec<GString> = self
+            .data_modified_times
+            .iter_mut()
+            .filter_map(|(path, modified_time)| {
+                let current = FileAccess::get_modified_time(&*path);
+                if current == *modified_time {
+                    return None;
+                }
+                *modified_time = current;
+                Some(path.clone())
+            })
+            .collect();
+        let mut reloaded: u32 = 0;
+        let mut abandoned: Vec<ThinkerProcess> = Vec::new();
+        for path in changed.iter() {
+            match self.reload_file(path) {
+                Ok(thinkers) => {
+                    godot_print!("reloaded {}", path);
+                    abandoned.extend(thinkers);
+                    reloaded += 1;
+                }
+                Err(e) => godot_error!("couldn't reload {}: {}", path, e),
+            }
+        }
+        if reloaded == 0 {
+            return 0;
+        }
+        // cached plans refer to the old action sets
+        self.plan_cache.lock().unwrap().clear();
+        let mut affected: Vec<u32> = abandoned.iter().map(|thinker| thinker.id).collect();
+        affected.sort();
+        affected.dedup();
+        self.validated_data_sets
+            .retain(|data_set| !data_set.iter().any(|path| changed.contains(path)));
+        for id in affected.iter() {
+            self.validate_thinker_data(*id);
+        }
+        // batch in flight might still apply plans made with the old data – planner resets them once again
+        if let Some(sender) = self.sender.as_mut() {
+            let _result = sender.send(ThinkerPlanEvent::ResetPlans(abandoned));
+        }
+        reloaded
+    }
+
+    /// broadcasts a stimulus – e.g. a sound – sensed by the thinkers within the given radius
+    #[func]
+    pub fn broadcast_stimulus(
+        &mut self,
+        stimulus_type: AIStimulusType,
+        position: Vector3,
+        radius: f32,
+        intensity: f32,
+        source: Option<Gd<Node>>,
+    ) {
+        let stimulus = AIStimulus {
+            stimulus_type,
+            position,
+            radius,
+            intensity,
+            source: source.map(|node| node.instance_id().into()),
+        };
+        for thinker in self.thinkers.values_mut() {
+            if !thinker.is_active {
+                continue;
+            }
+            let Some(base) = thinker.base.as_ref() else {
+                continue;
+            };
+            if base.get_global_position().distance_to(position) > radius {
+                continue;
+            }
+            thinker.stimuli.push(stimulus.clone());
+        }
+    }
+
+    /// queues a stimulus – e.g. received damage – for a given thinker regardless of its position
+    pub fn stimulate_thinker(&mut self, thinker_id: u32, stimulus: AIStimulus) {
+        let Some(thinker) = self.thinkers.get_mut(&thinker_id) else {
+            return;
+        };
+        if !thinker.is_active {
+            return;
+        }
+        thinker.stimuli.push(stimulus);
+    }
+
     #[func]
     fn unregister_thinker(&mut self, id: u32) {
         self.thinkers.remove(&id);
+        for squad in self.squads.values_mut() {
+            squad.members.retain(|member| *member != id);
+        }
     }
 
     #[func]
@@ -199,8 +399,11 @@ impl GodotAIManager {
             .as_ref()
             .map(|agent| agent.get_navigation_map());
 
+        let sensors = self
+            .get_sensors(&to_create.instance.bind().sensors_file)
+            .unwrap();
         let mut shared = ThinkerShared {
-            working_memory: Default::default(),
+            working_memory: WorkingMemory::default().with_policies(&sensors.fact_policies),
             blackboard: Default::default(),
             world_state: Self::load(&to_create.instance.bind().initial_state),
             target_mask: Default::default(),
@@ -217,26 +420,229 @@ impl GodotAIManager {
             goals: self
                 .get_goals(&to_create.instance.bind().goals_file)
                 .unwrap(),
-            polling_sensors: self
-                .get_sensors(&to_create.instance.bind().sensors_file)
-                .unwrap(),
+            polling_sensors: sensors.sensors,
+            event_sensor: sensors.event_sensors,
             animations: self
                 .get_animations_data(&to_create.instance.bind().animation_data)
                 .unwrap(),
+            attacks: self
+                .get_attacks(&to_create.instance.bind().attacks_file)
+                .unwrap(),
+            search_mode: to_create.instance.bind().search_mode,
+            plan_budget: to_create.instance.bind().plan_budget(),
             shared: Arc::new(Mutex::new(shared)),
             navigation_map_rid,
             ..Default::default()
         };
         self.thinkers.insert(id, thinker);
+        self.validate_thinker_data(id);
+        let files = {
+            let instance = to_create.instance.bind();
+            [
+                instance.actions_file.clone(),
+                instance.goals_file.clone(),
+                instance.sensors_file.clone(),
+                instance.animation_data.clone(),
+                instance.attacks_file.clone(),
+            ]
+        };
+        for path in files.into_iter().filter(|path| !path.is_empty()) {
+            let modified_time = FileAccess::get_modified_time(&path);
+            self.data_modified_times
+                .entry(path)
+                .or_insert(modified_time);
+        }
+        let squad = to_create.instance.bind().squad.clone();
+        if !squad.is_empty() {
+            if !self.squads.contains_key(&squad) {
+                let coordinator = self.get_squad_coordinator(&squad);
+                self.squads.insert(
+                    squad.clone(),
+                    Squad {
+                        coordinator,
+                        ..Default::default()
+                    },
+                );
+            }
+            self.squads
+                .get_mut(&squad)
+                .expect("no such squad!")
+                .members
+                .push(id);
+        }
         to_create.instance.bind_mut().thinker_id = id;
     }
 
+    /// reports problems with the data set of a given thinker, once per each combination of the data files
+    fn validate_thinker_data(&mut self, id: u32) {
+        let Some(thinker) = self.thinkers.get(&id) else {
+            return;
+        };
+        let Some(base) = thinker.base.as_ref() else {
+            return;
+        };
+        let data_set = {
+            let instance = base.bind();
+            [
+                instance.actions_file.clone(),
+                instance.goals_file.clone(),
+                instance.animation_data.clone(),
+                instance.sensors_file.clone(),
+            ]
+        };
+        if !self.validated_data_sets.insert(data_set.clone()) {
+            return;
+        }
+        let warnings = validate_data_set(
+            &thinker.actions,
+            &thinker.goals,
+            &thinker.animations,
+            &thinker.polling_sensors,
+            &thinker.event_sensor,
+        );
+        for warning in warnings.iter() {
+            godot_warn!(
+                "{} ({}, {}): {}",
+                data_set[0],
+                data_set[1],
+                data_set[3],
+                warning
+            );
+        }
+    }
+
     fn load<T: for<'a> Deserialize<'a>>(path: &GString) -> T {
-        let file = FileAccess::open(path, ModeFlags::READ);
-        file.as_ref()
-            .expect("Couldn't read given component config!");
-        ron::from_str::<T>(&String::from(file.unwrap().get_as_text()))
-            .expect("Couldn't read given component config!")
+        Self::try_load(path).expect("Couldn't read given component config!")
+    }
+
+    fn try_load<T: for<'a> Deserialize<'a>>(path: &GString) -> Result<T, String> {
+        let file = FileAccess::open(path, ModeFlags::READ)
+            .ok_or_else(|| format!("{:?}", FileAccess::get_open_error()))?;
+        ron::from_str::<T>(&String::from(file.get_as_text())).map_err(|e| e.to_string())
+    }
+
+    /// loads actions & resolves steps of the compound ones
+    fn try_load_actions(path: &GString) -> Result<Vec<ActionComponent>, String> {
+        let mut actions = Self::try_load::<Vec<ActionComponent>>(path)?;
+        resolve_compound_steps(&mut actions)?;
+        Ok(actions)
+    }
+
+    /// replaces cached data loaded from given path with the new one.
+    /// Returns the thinkers whose plans have been abandoned, together with the old data.
+    fn reload_file(&mut self, path: &GString) -> Result<Vec<ThinkerProcess>, String> {
+        let mut affected = Vec::new();
+        if self.actions.contains_key(path) {
+            let actions = Arc::new(Self::try_load_actions(path)?);
+            affected.extend(self.swap_cached(
+                path,
+                actions,
+                |cache| &mut cache.actions,
+                |t| &mut t.actions,
+            ));
+        }
+        if self.goals.contains_key(path) {
+            let goals = Arc::new(Self::try_load::<Vec<GoalComponent>>(path)?);
+            affected.extend(self.swap_cached(
+                path,
+                goals,
+                |cache| &mut cache.goals,
+                |t| &mut t.goals,
+            ));
+        }
+        if self.attacks.contains_key(path) {
+            let attacks = Arc::new(Self::try_load::<Vec<AttackData>>(path)?);
+            affected.extend(self.swap_cached(
+                path,
+                attacks,
+                |cache| &mut cache.attacks,
+                |t| &mut t.attacks,
+            ));
+        }
+        if self.animations.contains_key(path) {
+            let animations = Arc::new(AnimationsData::from(Self::try_load::<
+                HashMap<AnimationType, AnimationProps>,
+            >(path)?));
+            affected.extend(self.swap_cached(
+                path,
+                animations,
+                |cache| &mut cache.animations,
+                |t| &mut t.animations,
+            ));
+        }
+        if self.sensors_blueprint.contains_key(path) {
+            let sensors: SensorsConfig = Self::try_load(path)?;
+            for thinker in self.thinkers.values_mut() {
+                if thinker.base.as_ref().unwrap().bind().sensors_file != *path {
+                    continue;
+                }
+                thinker.polling_sensors = sensors.sensors.clone();
+                thinker.event_sensor = sensors.event_sensors.clone();
+                thinker
+                    .shared
+                    .lock()
+                    .unwrap()
+                    .working_memory
+                    .set_policies(&sensors.fact_policies);
+            }
+            self.sensors_blueprint.insert(path.clone(), sensors);
+        }
+        Ok(affected)
+    }
+
+    /// swaps cached data and the data of all the thinkers that shared it.
+    /// Plans of these thinkers are abandoned beforehand, while they still refer to the old data.
+    fn swap_cached<T>(
+        &mut self,
+        path: &GString,
+        new: Arc<T>,
+        cache: fn(&mut Self) -> &mut HashMap<GString, Arc<T>>,
+        field: fn(&mut Thinker) -> &mut Arc<T>,
+    ) -> Vec<ThinkerProcess> {
+        let Some(old) = cache(self).insert(path.clone(), new.clone()) else {
+            return Vec::new();
+        };
+        let mut abandoned = Vec::new();
+        for thinker in self.thinkers.values_mut() {
+            if !Arc::ptr_eq(field(thinker), &old) {
+                continue;
+            }
+            abandoned.push(Self::abandon_plan(
+                thinker,
+                &self.ai_nodes,
+                &self.plan_cache,
+            ));
+            *field(thinker) = new.clone();
+        }
+        abandoned
+    }
+
+    /// exits the running state & resets the plan of given thinker using its current data.
+    /// Returns the thinker process with that data.
+    fn abandon_plan(
+        thinker: &mut Thinker,
+        ai_nodes: &Arc<RwLock<HashMap<u32, AINode>>>,
+        plan_cache: &Arc<Mutex<PlanCache>>,
+    ) -> ThinkerProcess {
+        if let (Some(mut state), Some(base)) = (thinker.state.take(), thinker.base.clone()) {
+            let Ok(mut shared_guard) = thinker.shared.lock() else {
+                panic!("couldn't open thinker mutex!")
+            };
+            let shared = &mut *shared_guard;
+            let mut state_args = StateArguments {
+                base,
+                world_state: &mut shared.world_state,
+                working_memory: &mut shared.working_memory,
+                blackboard: &mut shared.blackboard,
+                delta: 0.0,
+            };
+            state.exit(&mut state_args);
+        }
+        let mut thinker_process = ThinkerProcess::from(&*thinker)
+            .with_ainodes(ai_nodes.clone())
+            .with_plan_cache(plan_cache.clone());
+        with_plan_view(&mut thinker_process, reset_plan);
+        thinker_process
     }
 
     /// loads & caches given set of components
@@ -258,13 +664,26 @@ impl GodotAIManager {
     }
 
     pub fn get_actions(&mut self, path: &GString) -> Option<Arc<Vec<ActionComponent>>> {
-        Self::load_components::<ActionComponent, ActionComponent>(&mut self.actions, path)
+        if let Some(actions) = self.actions.get(path) {
+            return Some(actions.clone());
+        }
+        let actions =
+            Arc::new(Self::try_load_actions(path).expect("Couldn't read given component config!"));
+        self.actions.insert(path.clone(), actions.clone());
+        Some(actions)
     }
 
     pub fn get_goals(&mut self, path: &GString) -> Option<Arc<Vec<GoalComponent>>> {
         Self::load_components::<GoalComponent, GoalComponent>(&mut self.goals, path)
     }
 
+    pub fn get_attacks(&mut self, path: &GString) -> Option<Arc<Vec<AttackData>>> {
+        if path.is_empty() {
+            return Some(Default::default());
+        }
+        Self::load_components::<AttackData, AttackData>(&mut self.attacks, path)
+    }
+
     pub fn get_animations_data(&mut self, path: &GString) -> Option<Arc<AnimationsData>> {
         if let Some(collection) = self.animations.get(path) {
             return Some(collection.clone());
@@ -277,11 +696,25 @@ impl GodotAIManager {
         Some(animations_data)
     }
 
-    fn get_sensors(&mut self, path: &GString) -> Option<Vec<PollingSensor>> {
+    /// coordinator of a given squad, configured in the squads file if there is any
+    fn get_squad_coordinator(&self, squad: &GString) -> SquadCoordinator {
+        if self.squads_file.is_empty() {
+            return SquadCoordinator::default();
+        }
+        match Self::try_load::<HashMap<String, SquadCoordinator>>(&self.squads_file) {
+            Ok(mut coordinators) => coordinators.remove(&squad.to_string()).unwrap_or_default(),
+            Err(e) => {
+                godot_warn!("couldn't load {}: {}", self.squads_file, e);
+                SquadCoordinator::default()
+            }
+        }
+    }
+
+    fn get_sensors(&mut self, path: &GString) -> Option<SensorsConfig> {
         if let Some(collection) = self.sensors_blueprint.get(path) {
             return Some(collection.clone());
         }
-        let components: Vec<PollingSensor> = Self::load(path);
+        let components: SensorsConfig = Self::load(path);
         self.sensors_blueprint
             .insert(path.clone(), components.clone());
         Some(components)
@@ -316,6 +749,14 @@ impl GameSystem for GodotAIManager {
     }
 
     fn physics_process(&mut self, delta: f64) {
+        if cfg!(debug_assertions) && self.watch_ai_data {
+            self.watch_ai_data_delta += delta;
+            if self.watch_ai_data_delta >= 1.0 {
+                self.watch_ai_data_delta = 0.0;
+                self.reload_ai_data();
+            }
+        }
+
         let mut memories: Vec<_> = self.thinkers.values().map(|t| t.shared.clone()).collect();
         memories.par_iter_mut().rev().for_each(|shared| {
             let Ok(mut shared) = shared.lock() else {
@@ -325,16 +766,28 @@ impl GameSystem for GodotAIManager {
         });
         drop(memories);
 
+        for squad in self.squads.values_mut() {
+            squad.process(&self.thinkers);
+        }
+
+        let mut batch = PlanBatch {
+            thinkers: Vec::with_capacity(self.thinkers.len()),
+            max_replans: self.max_replans_per_frame,
+        };
         for thinker in self.thinkers.values_mut() {
             if !thinker.is_active {
                 continue;
             }
             process_thinker(thinker, delta, &self.ai_nodes);
-            if let Some(sender) = self.sender.as_mut() {
-                let _result = sender.send(ThinkerPlanEvent::Process(
-                    ThinkerProcess::from(&*thinker).with_ainodes(self.ai_nodes.clone()),
-                ));
-            }
+            batch.thinkers.push(
+                ThinkerProcess::from(&*thinker)
+                    .with_ainodes(self.ai_nodes.clone())
+                    .with_plan_cache(self.plan_cache.clone()),
+            );
+        }
+        // plan for all the thinkers at once
+        if let Some(sender) = self.sender.as_mut() {
+            let _result = sender.send(ThinkerPlanEvent::Process(batch));
         }
     }
 }
@@ -1,12 +1,15 @@
+use crate::ai::ai_stimulus::AIStimulus;
+use crate::ai::planner::{PlanBudget, SearchMode};
 use crate::ai::working_memory::Event::AnimationCompleted;
 use crate::ai::working_memory::{AIStimuli, Desire, WMProperty};
 use crate::character_controler::character_controller_3d::CharacterController3D;
 use crate::godot_api::ai_manager::GodotAIManager;
 use crate::godot_api::gamesys::GameSystem;
-use crate::receiver::damage_receptor_component::ReceivedDamage;
+use crate::receiver::damage_receptor_component::{DamageReceptorComponent, ReceivedDamage};
 use crate::utils::generate_id::ToCreate;
 use godot::classes::{AnimationTree, Marker3D, NavigationAgent3D, Shape3D};
 use godot::prelude::*;
+use std::time::Duration;
 
 /// an interface to speak with AI manager
 #[derive(GodotClass)]
@@ -29,8 +32,23 @@ pub struct GodotThinker {
     pub(crate) sensors_file: GString,
     #[export(file = "*.ron")]
     pub(crate) animation_data: GString,
+    /// optional – thinkers without attacks never pick any
+    #[export(file = "*.ron")]
+    pub(crate) attacks_file: GString,
     #[export(file = "*.ron")]
     pub(crate) initial_state: GString,
+    /// name of the squad this thinker belongs to, empty – none
+    #[export]
+    pub(crate) squad: GString,
+    /// planner strategy – regressive search expands only actions relevant to the goal
+    #[export]
+    pub(crate) search_mode: SearchMode,
+    /// max number of nodes expanded by the planner in a single search, 0 – unlimited
+    #[export]
+    pub(crate) plan_max_nodes: u32,
+    /// max time (in seconds) spent by the planner in a single search, 0 – unlimited
+    #[export]
+    pub(crate) plan_max_time: f64,
     #[var(usage_flags = [GROUP, EDITOR, READ_ONLY])]
     references: u32,
     /// Area3D used to find nearby AI Nodes
@@ -42,6 +60,9 @@ pub struct GodotThinker {
     pub animation_tree: Option<Gd<AnimationTree>>,
     #[export]
     pub head_position: Option<Gd<Marker3D>>,
+    /// optional – used to tell how hurt the thinker is
+    #[export]
+    pub damage_receptor: Option<Gd<DamageReceptorComponent>>,
     #[var(usage_flags = [GROUP, EDITOR, READ_ONLY])]
     detection_shapes: u32,
     /// detection shapes
@@ -74,7 +95,18 @@ pub struct GodotThinker {
     base: Base<Node3D>,
 }
 
-impl GodotThinker {}
+impl GodotThinker {
+    pub fn plan_budget(&self) -> PlanBudget {
+        let mut budget = PlanBudget::default();
+        if self.plan_max_nodes > 0 {
+            budget = budget.with_max_nodes(self.plan_max_nodes);
+        }
+        if self.plan_max_time > 0.0 {
+            budget = budget.with_max_time(Duration::from_secs_f64(self.plan_max_time));
+        }
+        budget
+    }
+}
 
 #[godot_api]
 impl GodotThinker {
@@ -115,11 +147,21 @@ impl GodotThinker {
         if self.thinker_id == 0 {
             return;
         }
-        let fact = WMProperty::AIStimuli(AIStimuli::Damage(damage));
+        let stimulus = AIStimulus {
+            stimulus_type: damage.damage_type,
+            position: damage.pos,
+            radius: 0.0,
+            intensity: 1.0,
+            source: damage.damager.map(Into::into),
+        };
+        let fact = WMProperty::AIStimuli(AIStimuli::Damage(damage.into()));
         let mut ai_manager = GodotAIManager::singleton();
         ai_manager
             .bind_mut()
             .add_new_wm_fact(self.thinker_id, fact, 1.0, 30.0);
+        ai_manager
+            .bind_mut()
+            .stimulate_thinker(self.thinker_id, stimulus);
         ai_manager.bind_mut().invalidate_plan(self.thinker_id);
     }
 
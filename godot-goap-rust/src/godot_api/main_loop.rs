@@ -1,4 +1,5 @@
 use crate::act_react::act_react_executor::ActReactExecutor;
+use crate::ai::clock::GameClock;
 use crate::equipment::register_equipment_dispatch;
 use crate::godot_api::ai_manager::GodotAIManager;
 use crate::godot_api::gamesys::GameSystem;
@@ -11,6 +12,7 @@ struct AtlantianTorturewareGameLoop {
     inventory_manager: Option<Gd<InventoryManager>>,
     ai_manager: Option<Gd<GodotAIManager>>,
     act_react_executor: Option<Gd<ActReactExecutor>>,
+    base: Base<SceneTree>,
 }
 
 #[godot_api]
@@ -23,6 +25,10 @@ impl ISceneTree for AtlantianTorturewareGameLoop {
     }
 
     fn physics_process(&mut self, delta: f64) -> bool {
+        // delta is already scaled by the Engine time scale
+        if !self.base().is_paused() {
+            GameClock::global().advance(delta);
+        }
         self.ai_manager
             .as_mut()
             .unwrap()
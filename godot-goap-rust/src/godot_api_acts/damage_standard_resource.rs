@@ -1,5 +1,6 @@
 use crate::act_react::act_react_resource::Emitter;
 use crate::act_react::stimulis::Stimuli;
+use crate::ai::ai_stimulus::AIStimulusType;
 use godot::builtin::math::FloatExt;
 use godot::prelude::*;
 use rand::prelude::*;
@@ -29,6 +30,10 @@ pub struct ActDamageStandard {
     #[export]
     #[init(val = 0.)]
     pub pain_range: f64,
+    /// stimulus sensed by the damaged thinkers
+    #[export]
+    #[init(val = AIStimulusType::DamageBullet)]
+    pub damage_type: AIStimulusType,
 }
 
 impl ActDamageStandard {
@@ -66,7 +71,8 @@ impl Emitter for ActDamageStandard {
         dict! {
             "strength": strength,
             "force": force,
-            "pain": pain
+            "pain": pain,
+            "damage_type": self.damage_type
         }
     }
 }
@@ -2,7 +2,7 @@
 // Act resource contains information about invoked stimuli and some additional data
 mod combine_resource;
 mod damage_standard_resource;
-mod frob_resource;
+pub(crate) mod frob_resource;
 mod player_frob_resource;
 mod player_pressure_resource;
 mod pressure_resource;
@@ -0,0 +1,76 @@
+use crate::act_react::act_react_resource::Reaction;
+use crate::act_react::game_effect::{EffectResult, GameEffect};
+use crate::ai::ai_stimulus::AIStimulusType;
+use crate::godot_api::ai_manager::GodotAIManager;
+use crate::godot_api::gamesys::GameSystem;
+use godot::prelude::*;
+
+/// broadcasts a stimulus sensed by the nearby thinkers, e.g. a sound of a breaking object
+#[derive(GodotClass, Debug)]
+#[class(init, base=Resource)]
+pub struct MakeNoiseGameEffect {
+    #[export]
+    #[init(val = AIStimulusType::DisturbanceSound)]
+    pub stimulus_type: AIStimulusType,
+    #[export]
+    #[init(val = 10.0)]
+    pub radius: f32,
+    #[export]
+    #[init(val = 1.0)]
+    pub intensity: f32,
+    base: Base<Resource>,
+}
+
+#[godot_dyn]
+impl Reaction for MakeNoiseGameEffect {
+    fn build_effect(
+        &self,
+        _act_context: &Dictionary,
+        context: &Dictionary,
+    ) -> Option<DynGd<Object, dyn GameEffect>> {
+        let position = context
+            .get("position")
+            .and_then(|v| v.try_to::<Vector3>().ok())
+            .or_else(|| {
+                context
+                    .get("reactor")
+                    .and_then(|v| v.try_to::<Gd<Node3D>>().ok())
+                    .map(|reactor| reactor.get_global_position())
+            })?;
+        let make_noise = MakeNoise {
+            stimulus_type: self.stimulus_type,
+            position,
+            radius: self.radius,
+            intensity: self.intensity,
+            source: context
+                .get("actor")
+                .and_then(|v| v.try_to::<Gd<Node>>().ok()),
+        };
+        let obj = Gd::from_object(make_noise);
+        Some(obj.into_dyn::<dyn GameEffect>().upcast())
+    }
+}
+
+#[derive(GodotClass, Debug)]
+#[class(init, base=Object)]
+pub struct MakeNoise {
+    pub stimulus_type: AIStimulusType,
+    pub position: Vector3,
+    pub radius: f32,
+    pub intensity: f32,
+    pub source: Option<Gd<Node>>,
+}
+
+#[godot_dyn]
+impl GameEffect for MakeNoise {
+    fn execute(&mut self) -> EffectResult {
+        GodotAIManager::singleton().bind_mut().broadcast_stimulus(
+            self.stimulus_type,
+            self.position,
+            self.radius,
+            self.intensity,
+            self.source.take(),
+        );
+        EffectResult::Free
+    }
+}
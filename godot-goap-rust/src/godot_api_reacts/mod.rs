@@ -7,5 +7,6 @@ mod call_method;
 mod combine;
 pub mod fly;
 mod grab;
+mod make_noise;
 mod pickup;
 mod print_message;
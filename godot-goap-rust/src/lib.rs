@@ -2,10 +2,11 @@ pub mod act_react;
 pub mod ai;
 mod ai_nodes;
 mod animations;
+mod attacks;
 mod character_controler;
 mod equipment;
-mod goap_actions;
-mod goap_goals;
+pub mod goap_actions;
+pub mod goap_goals;
 mod godot_api;
 pub mod godot_api_acts;
 pub mod godot_api_reacts;
@@ -1,5 +1,7 @@
+use crate::ai::ai_stimulus::AIStimulusType;
 use crate::character_controler::character_controller_3d::CharacterController3D;
-use crate::godot_api::gamesys::GameSys;
+use crate::godot_api::ai_manager::GodotAIManager;
+use crate::godot_api::gamesys::{GameSys, GameSystem};
 use godot::classes::input::MouseMode;
 use godot::classes::{InputEvent, InputEventMouseMotion, ShapeCast3D};
 use godot::global::{fmod, lerpf, sin};
@@ -41,6 +43,15 @@ pub struct PlayerCameraController3D {
     #[export]
     #[init(val = 0.1)]
     step_damping_time: f32,
+    /// distance walked between two footsteps
+    #[export]
+    #[init(val = 2.0)]
+    footstep_distance: f32,
+    /// radius within which thinkers can hear the footsteps
+    #[export]
+    #[init(val = 6.0)]
+    footstep_noise_radius: f32,
+    walked_distance: f32,
     current_bob: Vector3,
     damping_time: Option<SystemTime>,
     original_camera_pos: Vector3,
@@ -144,6 +155,32 @@ impl PlayerCameraController3D {
         sin(self.camera_data.bobtimes[axis] as f64) as f32
     }
 
+    /// makes a footstep noise every `footstep_distance` walked on the ground
+    fn make_footstep_noise(&mut self, delta: f32) {
+        let Some(char) = self.character_controller.as_ref() else {
+            return;
+        };
+        let walked = char
+            .bind()
+            .movement_data
+            .as_ref()
+            .filter(|md| md.grounded)
+            .map(|md| (md.velocity * Vector3::new(1.0, 0.0, 1.0)).length() * delta)
+            .unwrap_or(0.0);
+        self.walked_distance += walked;
+        if self.walked_distance < self.footstep_distance {
+            return;
+        }
+        self.walked_distance = 0.0;
+        GodotAIManager::singleton().bind_mut().broadcast_stimulus(
+            AIStimulusType::FootstepSound,
+            char.get_global_position(),
+            self.footstep_noise_radius,
+            1.0,
+            Some(char.clone().upcast()),
+        );
+    }
+
     fn calculate_bob(&mut self, delta: f32) -> Vector3 {
         Vector3::new(
             self.get_and_update_bob(delta, self.bob_frequency.x, 0),
@@ -161,6 +198,7 @@ impl INode for PlayerCameraController3D {
         }
         self.perform_mouse_rotation(delta as f32);
         self.tilt_camera(delta as f32);
+        self.make_footstep_noise(delta as f32);
         let bob = self.calculate_bob(delta as f32);
 
         let Some(head) = self.head.as_mut() else {
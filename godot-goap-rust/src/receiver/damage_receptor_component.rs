@@ -1,6 +1,8 @@
+use crate::ai::ai_stimulus::AIStimulusType;
+use crate::ai::clock::GameClock;
+use crate::ai::working_memory::DamageStimulus;
 use godot::prelude::*;
 use std::fmt::Debug;
-use std::time::SystemTime;
 
 #[derive(Clone, Debug)]
 pub struct ReceivedDamage {
@@ -10,6 +12,7 @@ pub struct ReceivedDamage {
     pub pos: Vector3,
     pub normal: Vector3,
     pub direction: Vector3,
+    pub damage_type: AIStimulusType,
 }
 
 impl GodotConvert for ReceivedDamage {
@@ -35,6 +38,12 @@ impl FromGodot for ReceivedDamage {
             .map(|v| v.to::<Vector3>())
             .unwrap_or(Vector3::ZERO);
         let damager = via.get("actor").map(|v| v.to::<Gd<Node>>().instance_id());
+        // damage emitted without a type is treated as a bullet hit
+        let damage_type = via
+            .get("damage_type")
+            .and_then(|v| v.try_to::<AIStimulusType>().ok())
+            .filter(AIStimulusType::is_damage)
+            .unwrap_or(AIStimulusType::DamageBullet);
         Ok(Self {
             damager,
             strength,
@@ -42,6 +51,7 @@ impl FromGodot for ReceivedDamage {
             pos,
             normal,
             direction,
+            damage_type,
         })
     }
 }
@@ -57,6 +67,20 @@ impl ToGodot for ReceivedDamage {
             "normal": self.normal,
             "actor": self.damager.unwrap(),
             "direction": self.direction,
+            "damage_type": self.damage_type,
+        }
+    }
+}
+
+impl From<ReceivedDamage> for DamageStimulus {
+    fn from(damage: ReceivedDamage) -> Self {
+        DamageStimulus {
+            damager: damage.damager.map(Into::into),
+            strength: damage.strength,
+            pain: damage.pain,
+            pos: damage.pos.into(),
+            normal: damage.normal.into(),
+            direction: damage.direction.into(),
         }
     }
 }
@@ -85,13 +109,14 @@ pub struct DamageReceptorComponent {
     #[export(range = (0.0, 1.0))]
     #[init(val = 0.1)]
     pub gib_threshold: f64,
-    time_since_last_pain_state: Option<SystemTime>,
+    /// game time of entering the pain state
+    time_since_last_pain_state: Option<f64>,
     #[export]
     pain_recovery: f64,
     #[export]
     pain_recovery_cooldown: f64,
-    #[init(val = SystemTime::now())]
-    time_since_last_pain: SystemTime,
+    /// game time of receiving the last pain
+    time_since_last_pain: f64,
     base: Base<Node>,
 }
 
@@ -127,24 +152,23 @@ impl INode for DamageReceptorComponent {
                 .emit_signal("health_depleted", &[is_gib.to_variant()]);
         }
 
+        let clock = GameClock::global();
         if self.pain_taken > 0. {
             let is_immune_to_pain = self
                 .time_since_last_pain_state
-                .map(|lp| lp.elapsed().unwrap().as_secs_f64() < self.pain_resistance_time)
+                .map(|lp| clock.elapsed_since(lp) < self.pain_resistance_time)
                 .unwrap_or(false);
             if !is_immune_to_pain {
                 self.pain += self.pain_taken;
                 if self.pain > self.pain_threshold {
-                    self.time_since_last_pain_state = Some(SystemTime::now());
+                    self.time_since_last_pain_state = Some(clock.now());
                     self.pain = 0.;
                     self.base_mut().emit_signal("pain_threshold_achieved", &[]);
                 }
-                self.time_since_last_pain = SystemTime::now();
+                self.time_since_last_pain = clock.now();
             }
             self.pain_taken = 0.;
-        } else if self.time_since_last_pain.elapsed().unwrap().as_secs_f64()
-            > self.pain_recovery_cooldown
-        {
+        } else if clock.elapsed_since(self.time_since_last_pain) > self.pain_recovery_cooldown {
             self.pain = (self.pain - self.pain_recovery * delta).max(0.);
         }
     }
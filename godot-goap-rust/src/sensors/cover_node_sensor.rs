@@ -0,0 +1,193 @@
+use crate::ai::blackboard::NavigationTarget;
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, Node, WMNodeType, WMProperty, WorkingMemoryFactTypeKey,
+};
+use crate::ai::world_state::{CoverStatusType, WSProperty, WorldStateProperty};
+use crate::ai_nodes::ai_node::AINode;
+use crate::ai_nodes::godot_ai_node::AINodeType;
+use crate::sensors::sensor_types::{SensorPolling, ThinkerProcessArgs};
+use crate::targeting::target::AITarget;
+use godot::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// sensor responsible for scoring the Cover, Hide & Ambush nodes against the current target
+/// and telling if the thinker is covered.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoverNodeSensor {
+    update_every: f64,
+    last_update_delta: f64,
+    /// nodes further away from the thinker are ignored
+    max_distance: f32,
+    /// nodes closer to the target are ignored
+    #[serde(default = "min_target_distance_default")]
+    min_target_distance: f32,
+}
+
+fn min_target_distance_default() -> f32 {
+    4.0
+}
+
+impl CoverNodeSensor {
+    /// thinker closer to its node is considered to be standing on it
+    const AT_NODE_DIST: f32 = 1.0;
+    const NODE_TYPES: [WMNodeType; 3] = [WMNodeType::Cover, WMNodeType::Hide, WMNodeType::Ambush];
+
+    fn forget_nodes(args: &mut ThinkerProcessArgs) {
+        for node_type in Self::NODE_TYPES {
+            let fact_query = FactQuery::with_check(FactQueryCheck::Node(node_type));
+            while args
+                .working_memory
+                .find_and_mark_as_invalid(fact_query.clone())
+                .is_some()
+            {}
+        }
+    }
+
+    /// frees the node that has been left or is no longer needed
+    fn release_node(args: &mut ThinkerProcessArgs) {
+        let Some(ainode_id) = args.blackboard.current_locked_node else {
+            return;
+        };
+        let Ok(ainodes_guard) = args.ainodes.read() else {
+            panic!("rwlock failed!")
+        };
+        if let Some(node) = ainodes_guard.get(&ainode_id) {
+            if !matches!(
+                node,
+                AINode::Cover { .. } | AINode::Hide { .. } | AINode::Ambush { .. }
+            ) {
+                return;
+            }
+            let position = node.base().position;
+            let is_heading_there = match args.blackboard.navigation_target {
+                Some(NavigationTarget::Position(destination)) => {
+                    destination.is_equal_approx(position)
+                }
+                _ => false,
+            };
+            let is_at_node =
+                position.distance_to(args.blackboard.thinker_position) < Self::AT_NODE_DIST;
+            if is_heading_there || (is_at_node && args.blackboard.target.is_some()) {
+                return;
+            }
+            node.base().unlock(args.id);
+        }
+        args.blackboard.current_locked_node = None;
+    }
+
+    fn cover_status(args: &ThinkerProcessArgs, target_position: Vector3) -> CoverStatusType {
+        let Some(ainode_id) = args.blackboard.current_locked_node else {
+            return CoverStatusType::Exposed;
+        };
+        let Ok(ainodes_guard) = args.ainodes.read() else {
+            panic!("rwlock failed!")
+        };
+        let Some(node) = ainodes_guard.get(&ainode_id) else {
+            return CoverStatusType::Exposed;
+        };
+        let distance = node
+            .base()
+            .position
+            .distance_to(args.blackboard.thinker_position);
+        // only the Cover, Hide & Ambush nodes protect the thinker
+        if distance < Self::AT_NODE_DIST && node.is_valid_against(target_position) {
+            return CoverStatusType::Covered;
+        }
+        CoverStatusType::Exposed
+    }
+
+    /// valid nodes with their score – the closer to the thinker the better
+    fn score_nodes(
+        &self,
+        args: &mut ThinkerProcessArgs,
+        target_position: Vector3,
+    ) -> Vec<(Node, f32)> {
+        let thinker_position = args.blackboard.thinker_position;
+        let id = args.id;
+        let Some(ainodes) = args.polls.get_ainodes() else {
+            return Vec::new();
+        };
+        let Ok(ainodes_guard) = args.ainodes.read() else {
+            panic!("rwlock failed!")
+        };
+        let mut scored = Vec::new();
+        for (node_id, node_type) in ainodes {
+            if !matches!(
+                node_type,
+                AINodeType::Cover | AINodeType::Hide | AINodeType::Ambush
+            ) {
+                continue;
+            }
+            let Some(node) = ainodes_guard.get(node_id) else {
+                continue;
+            };
+            if node.is_locked_not_by(id) || !node.is_valid_against(target_position) {
+                continue;
+            }
+            let position = node.base().position;
+            let distance = position.distance_to(thinker_position);
+            if distance > self.max_distance
+                || position.distance_to(target_position) < self.min_target_distance
+            {
+                continue;
+            }
+            let (ainode_id, position) = (*node_id, position.into());
+            let fact = match node {
+                AINode::Cover { .. } => Node::Cover {
+                    ainode_id,
+                    position,
+                },
+                AINode::Hide { .. } => Node::Hide {
+                    ainode_id,
+                    position,
+                },
+                AINode::Ambush { .. } => Node::Ambush {
+                    ainode_id,
+                    position,
+                },
+                _ => continue,
+            };
+            let score = (1.0 - distance / self.max_distance).max(0.01);
+            scored.push((fact, score));
+        }
+        scored
+    }
+}
+
+impl SensorPolling for CoverNodeSensor {
+    fn written_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Node]
+    }
+
+    fn process(&mut self, delta: f64, args: &mut ThinkerProcessArgs) -> bool {
+        self.last_update_delta += delta;
+        if self.last_update_delta < self.update_every {
+            return false;
+        }
+        self.last_update_delta = 0.0;
+        Self::release_node(args);
+        // nodes are scored against the target, so their old scores are no longer valid
+        Self::forget_nodes(args);
+        let Some(target_position) = args
+            .blackboard
+            .target
+            .as_ref()
+            .and_then(AITarget::get_target_pos)
+        else {
+            args.world_state[WorldStateProperty::CoverStatus] = None;
+            return false;
+        };
+        args.world_state[WorldStateProperty::CoverStatus] = Some(WSProperty::CoverStatus(
+            Self::cover_status(args, target_position),
+        ));
+
+        for (fact, score) in self.score_nodes(args, target_position) {
+            args.working_memory.add_working_memory_fact(
+                WMProperty::Node(fact),
+                score,
+                self.update_every * 2.0,
+            );
+        }
+        false
+    }
+}
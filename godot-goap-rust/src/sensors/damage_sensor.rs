@@ -1,12 +1,48 @@
-use crate::ai::ai_stimulus::AIStimulusType;
+use crate::ai::ai_stimulus::AIStimulus;
+use crate::ai::working_memory::{Knowledge, WMProperty, WorkingMemoryFactTypeKey};
 use crate::sensors::sensor_types::{SensorEvent, ThinkerProcessArgs};
+use crate::targeting::targeting_systems::TargetMask;
 use serde::{Deserialize, Serialize};
 
+/// sensor responsible for noticing the received damage – reveals the damager & forces replanning
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DamageSensor {}
+pub struct DamageSensor {
+    /// number of seconds the damager is remembered for
+    #[serde(default = "remember_damager_for_default")]
+    remember_damager_for: f64,
+}
+
+fn remember_damager_for_default() -> f64 {
+    240.0
+}
 
 impl SensorEvent for DamageSensor {
-    fn stimulate(&mut self, _stim: AIStimulusType, _args: &mut ThinkerProcessArgs) -> bool {
+    fn written_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[
+            WorkingMemoryFactTypeKey::Disturbance,
+            WorkingMemoryFactTypeKey::Knowledge,
+        ]
+    }
+
+    fn stimulate(&mut self, stimulus: &AIStimulus, args: &mut ThinkerProcessArgs) -> bool {
+        if !stimulus.stimulus_type.is_damage() {
+            return false;
+        }
+        args.working_memory
+            .add_or_update(WMProperty::Disturbance, 1.0, 30.0);
+        args.blackboard.invalidate_plan = true;
+        let Some(damager) = stimulus.source else {
+            return true;
+        };
+        let knowledge = WMProperty::Knowledge(Knowledge::Character(damager, None));
+        args.working_memory
+            .add_or_update(knowledge, 1.0, self.remember_damager_for);
+        // force retargeting
+        args.blackboard.invalidate_target = true;
+        args.blackboard.valid_targets = args
+            .blackboard
+            .valid_targets
+            .union(TargetMask::VisibleCharacter);
         true
     }
 }
@@ -28,18 +28,24 @@ impl SensorPolling for DistanceToTargetSensor {
 
         // bail if target is not visible
         let fact_query = FactQuery::with_check(FactQueryCheck::Match(WMProperty::AIStimuli(
-            AIStimuli::Character(*character_id, None),
+            AIStimuli::Character((*character_id).into(), None),
         )));
         let Some(WMProperty::AIStimuli(AIStimuli::Character(_, Some(position)))) =
             args.working_memory.find_fact(fact_query).map(|f| &f.f_type)
         else {
             args.blackboard.invalidate_target = true;
             args.world_state[WorldStateProperty::DistanceToTarget] = None;
+            args.world_state[WorldStateProperty::TargetDistance] = None;
             return false;
         };
 
-        let distance_to_target = args.blackboard.thinker_position.distance_to(*position);
+        let distance_to_target = args
+            .blackboard
+            .thinker_position
+            .distance_to((*position).into());
         args.blackboard.distance_to_target = Some(distance_to_target);
+        args.world_state[WorldStateProperty::TargetDistance] =
+            Some(WSProperty::Number(distance_to_target.round() as i32));
 
         if distance_to_target <= self.distance_close {
             args.world_state[WorldStateProperty::DistanceToTarget] =
@@ -1,4 +1,6 @@
-use crate::ai::working_memory::{FactQuery, FactQueryCheck, Node, WMNodeType, WMProperty};
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, Node, WMNodeType, WMProperty, WorkingMemoryFactTypeKey,
+};
 use crate::ai_nodes::ai_node::AINode;
 use crate::ai_nodes::godot_ai_node::AINodeType;
 use crate::sensors::sensor_types::SensorPolling;
@@ -65,6 +67,10 @@ impl PatrolPointSensor {
 }
 
 impl SensorPolling for PatrolPointSensor {
+    fn written_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Node]
+    }
+
     fn process(&mut self, delta: f64, args: &mut ThinkerProcessArgs) -> bool {
         self.last_update_delta += delta;
         // bail if we have some target
@@ -89,7 +95,7 @@ impl SensorPolling for PatrolPointSensor {
             args.working_memory.add_working_memory_fact(
                 WMProperty::Node(Node::Patrol {
                     ainode_id: node,
-                    position: pos,
+                    position: pos.into(),
                 }),
                 1.0,
                 self.update_every * 4.0,
@@ -0,0 +1,82 @@
+use crate::ai::ai_stimulus::AIStimulus;
+use crate::ai::working_memory::{Knowledge, WMProperty, WorkingMemoryFactTypeKey};
+use crate::sensors::sensor_types::{SensorEvent, ThinkerProcessArgs};
+use crate::targeting::targeting_systems::TargetMask;
+use serde::{Deserialize, Serialize};
+
+/// sensor responsible for hearing the sounds broadcast into the world
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HearingSensor {
+    /// multiplies the intensity of the heard sounds
+    #[serde(default = "sensitivity_default")]
+    sensitivity: f32,
+    /// sounds heard at least this loud reveal the character that made them
+    #[serde(default = "reveal_threshold_default")]
+    reveal_threshold: f32,
+    /// number of seconds the position of the disturbance is remembered for
+    #[serde(default = "remember_for_default")]
+    remember_for: f64,
+    /// number of seconds the revealed character is remembered for
+    #[serde(default = "remember_character_for_default")]
+    remember_character_for: f64,
+}
+
+fn sensitivity_default() -> f32 {
+    1.0
+}
+
+fn reveal_threshold_default() -> f32 {
+    0.5
+}
+
+fn remember_for_default() -> f64 {
+    20.0
+}
+
+fn remember_character_for_default() -> f64 {
+    240.0
+}
+
+impl SensorEvent for HearingSensor {
+    fn written_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Knowledge]
+    }
+
+    fn stimulate(&mut self, stimulus: &AIStimulus, args: &mut ThinkerProcessArgs) -> bool {
+        if !stimulus.stimulus_type.is_sound() {
+            return false;
+        }
+        let confidence =
+            (stimulus.attenuated_intensity(args.head_position) * self.sensitivity).min(1.0);
+        if confidence <= 0.0 {
+            return false;
+        }
+        // source of the sound is worth investigating, the same way as the last known position of the lost target
+        let disturbance =
+            WMProperty::Knowledge(Knowledge::LastTargetPosition(stimulus.position.into()));
+        args.working_memory
+            .add_or_update(disturbance, confidence, self.remember_for);
+        // force retargeting
+        args.blackboard.invalidate_target = true;
+        args.blackboard.valid_targets = args
+            .blackboard
+            .valid_targets
+            .union(TargetMask::LastKnownPosition);
+
+        let Some(source) = stimulus.source else {
+            return true;
+        };
+        if confidence < self.reveal_threshold {
+            return true;
+        }
+        let knowledge =
+            WMProperty::Knowledge(Knowledge::Character(source, Some(stimulus.position.into())));
+        args.working_memory
+            .add_or_update(knowledge, confidence, self.remember_character_for);
+        args.blackboard.valid_targets = args
+            .blackboard
+            .valid_targets
+            .union(TargetMask::VisibleCharacter);
+        true
+    }
+}
@@ -1,6 +1,9 @@
+mod cover_node_sensor;
 mod damage_sensor;
 mod distance_to_target_sensor;
 pub mod get_patrol_points_sensor;
+mod hearing_sensor;
 pub mod sensor_types;
+mod smart_object_sensor;
 mod target_lost_sensor;
 mod vision_character_sensor;
@@ -1,11 +1,15 @@
-use crate::ai::ai_stimulus::AIStimulusType;
+use crate::ai::ai_stimulus::AIStimulus;
 use crate::ai::blackboard::Blackboard;
-use crate::ai::working_memory::WorkingMemory;
+use crate::ai::working_memory::{FactPolicy, WorkingMemory, WorkingMemoryFactTypeKey};
 use crate::ai::world_state::WorldState;
 use crate::ai_nodes::ai_node::AINode;
+use crate::sensors::cover_node_sensor::CoverNodeSensor;
 use crate::sensors::damage_sensor::DamageSensor;
 use crate::sensors::distance_to_target_sensor::DistanceToTargetSensor;
 use crate::sensors::get_patrol_points_sensor::PatrolPointSensor;
+use crate::sensors::hearing_sensor::HearingSensor;
+use crate::sensors::smart_object_sensor::SmartObjectSensor;
+use crate::sensors::target_lost_sensor::TargetLostSensor;
 use crate::sensors::vision_character_sensor::VisionCharacterSensor;
 use crate::targeting::targeting_systems::TargetMask;
 use crate::thinker_states::polling::PollingResult;
@@ -14,6 +18,7 @@ use godot::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, RwLock};
+use strum_macros::IntoStaticStr;
 
 #[allow(unused_attributes, dead_code)]
 #[derive(Debug)]
@@ -33,27 +38,51 @@ pub struct ThinkerProcessArgs<'a> {
 
 #[allow(clippy::enum_variant_names)]
 #[enum_dispatch]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, IntoStaticStr)]
 pub enum EventSensor {
     DamageSensor,
+    HearingSensor,
 }
 
 #[allow(clippy::enum_variant_names)]
 #[enum_dispatch]
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, IntoStaticStr)]
 pub enum PollingSensor {
     PatrolPointSensor,
     VisionCharacterSensor,
     DistanceToTargetSensor,
+    TargetLostSensor,
+    CoverNodeSensor,
+    SmartObjectSensor,
+}
+
+/// contents of the sensors file – sensors of the thinker & policies of the facts they write
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SensorsConfig {
+    pub sensors: Vec<PollingSensor>,
+    #[serde(default)]
+    pub event_sensors: Vec<EventSensor>,
+    #[serde(default)]
+    pub fact_policies: HashMap<WorkingMemoryFactTypeKey, FactPolicy>,
 }
 
 #[enum_dispatch(PollingSensor)]
 pub trait SensorPolling {
     fn process(&mut self, delta: f64, args: &mut ThinkerProcessArgs) -> bool;
+
+    /// types of the facts written by the sensor, used to validate the data set
+    fn written_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[]
+    }
 }
 
 #[enum_dispatch(EventSensor)]
 pub trait SensorEvent {
     /// stimulate given sensor with given stim. Returns true if stimulus has been consumed
-    fn stimulate(&mut self, _stim: AIStimulusType, _args: &mut ThinkerProcessArgs) -> bool;
+    fn stimulate(&mut self, stimulus: &AIStimulus, args: &mut ThinkerProcessArgs) -> bool;
+
+    /// types of the facts written by the sensor, used to validate the data set
+    fn written_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[]
+    }
 }
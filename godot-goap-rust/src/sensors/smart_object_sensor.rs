@@ -0,0 +1,66 @@
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, Node, WMProperty, WorkingMemoryFactTypeKey,
+};
+use crate::ai_nodes::godot_ai_node::AINodeType;
+use crate::sensors::sensor_types::{SensorPolling, ThinkerProcessArgs};
+use serde::{Deserialize, Serialize};
+
+/// sensor responsible for finding free smart objects around the idle thinker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmartObjectSensor {
+    update_every: f64,
+    last_update_delta: f64,
+}
+
+impl SmartObjectSensor {
+    /// smart object the thinker is standing at has just been used
+    const MINIMAL_DIST: f32 = 2.0;
+}
+
+impl SensorPolling for SmartObjectSensor {
+    fn written_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Node]
+    }
+
+    fn process(&mut self, delta: f64, args: &mut ThinkerProcessArgs) -> bool {
+        self.last_update_delta += delta;
+        // bail if we have some target
+        if args.blackboard.target.is_some() {
+            return false;
+        }
+        if self.last_update_delta < self.update_every {
+            return false;
+        }
+        self.last_update_delta = 0.0;
+        let thinker_position = args.blackboard.thinker_position;
+        let Some(ainodes) = args.polls.get_ainodes() else {
+            return false;
+        };
+        let Ok(ainodes_guard) = args.ainodes.read() else {
+            panic!("rwlock failed!")
+        };
+        for (node_id, node_type) in ainodes {
+            if *node_type != AINodeType::SmartObject {
+                continue;
+            }
+            let Some(node) = ainodes_guard.get(node_id) else {
+                continue;
+            };
+            let position = node.base().position;
+            let fact = WMProperty::Node(Node::SmartObject {
+                ainode_id: *node_id,
+                position: position.into(),
+            });
+            if node.is_locked_not_by(args.id)
+                || position.distance_to(thinker_position) < Self::MINIMAL_DIST
+            {
+                args.working_memory
+                    .mark_as_invalid(FactQuery::with_check(FactQueryCheck::Match(fact)));
+                continue;
+            }
+            args.working_memory
+                .add_or_update(fact, 1.0, self.update_every * 2.0);
+        }
+        false
+    }
+}
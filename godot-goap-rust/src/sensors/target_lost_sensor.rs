@@ -1 +1,74 @@
+use crate::ai::types::EntityId;
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, Knowledge, WMKnowledgeType, WMProperty, WorkingMemoryFactTypeKey,
+};
+use crate::sensors::sensor_types::{SensorPolling, ThinkerProcessArgs};
+use crate::targeting::target::AITarget;
+use crate::targeting::targeting_systems::TargetMask;
+use serde::{Deserialize, Serialize};
 
+/// sensor responsible for noticing that the target character has been lost.
+/// Replaces the knowledge about the character with its last known position.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TargetLostSensor {
+    update_every: f64,
+    last_update_delta: f64,
+    /// number of seconds since the character has been sensed last time after which it is considered lost
+    lost_after: f64,
+    /// number of seconds the last known position is remembered for
+    remember_for: f64,
+}
+
+impl SensorPolling for TargetLostSensor {
+    fn written_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[WorkingMemoryFactTypeKey::Knowledge]
+    }
+
+    fn process(&mut self, delta: f64, args: &mut ThinkerProcessArgs) -> bool {
+        self.last_update_delta += delta;
+        if self.last_update_delta < self.update_every {
+            return false;
+        }
+        self.last_update_delta = 0.0;
+        let Some(AITarget::Character(id, _)) = args.blackboard.target.as_ref() else {
+            return false;
+        };
+        let character = EntityId::from(*id);
+        let character_query = FactQuery::with_check(FactQueryCheck::Match(WMProperty::Knowledge(
+            Knowledge::Character(character, None),
+        )));
+        let Some(fact) = args.working_memory.find_fact(character_query.clone()) else {
+            return false;
+        };
+        if args.working_memory.now() - fact.update_time < self.lost_after {
+            return false;
+        }
+        let last_known_position = match &fact.f_type {
+            WMProperty::Knowledge(Knowledge::Character(_, position)) => *position,
+            _ => None,
+        };
+
+        args.working_memory.mark_as_invalid(character_query);
+        let fact_query = FactQuery::with_check(FactQueryCheck::Knowledge(
+            WMKnowledgeType::LastTargetPosition,
+        ));
+        args.working_memory.mark_as_invalid(fact_query);
+        if let Some(position) = last_known_position {
+            args.working_memory.add_working_memory_fact(
+                WMProperty::Knowledge(Knowledge::LastTargetPosition(position)),
+                1.0,
+                self.remember_for,
+            );
+        }
+        // squad won't share the sightings the thinker already knows to be outdated
+        let now = args.working_memory.now();
+        args.blackboard.lost_characters.insert(character, now);
+        // force retargeting
+        args.blackboard.invalidate_target = true;
+        args.blackboard.valid_targets = args
+            .blackboard
+            .valid_targets
+            .union(TargetMask::LastKnownPosition);
+        false
+    }
+}
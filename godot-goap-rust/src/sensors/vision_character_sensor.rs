@@ -1,12 +1,14 @@
+use crate::ai::types::{EntityId, Vec3};
 use crate::ai::working_memory::Desire::Surprise;
-use crate::ai::working_memory::{AIStimuli, FactQuery, FactQueryCheck, Knowledge, WMProperty};
+use crate::ai::working_memory::{
+    AIStimuli, FactQuery, FactQueryCheck, Knowledge, WMProperty, WorkingMemoryFactTypeKey,
+};
 use crate::godot_api::godot_visible_area_3d::GodotVisibilityArea3D;
 use crate::sensors::sensor_types::{SensorPolling, ThinkerProcessArgs};
 use crate::targeting::targeting_systems::TargetMask;
 use godot::classes::{PhysicsRayQueryParameters3D, PhysicsServer3D};
 use godot::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::time::SystemTime;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct VisionCharacterSensor {
@@ -15,12 +17,21 @@ pub struct VisionCharacterSensor {
 }
 
 impl SensorPolling for VisionCharacterSensor {
+    fn written_facts(&self) -> &'static [WorkingMemoryFactTypeKey] {
+        &[
+            WorkingMemoryFactTypeKey::AIStimuli,
+            WorkingMemoryFactTypeKey::Knowledge,
+            WorkingMemoryFactTypeKey::Desire,
+        ]
+    }
+
     fn process(&mut self, delta: f64, args: &mut ThinkerProcessArgs) -> bool {
         self.last_update_delta += delta;
         if self.last_update_delta < self.update_every {
             return false;
         }
         self.last_update_delta = 0.0;
+        let now = args.working_memory.now();
 
         if args.polls.get_visible().is_none() {
             return false;
@@ -68,8 +79,8 @@ impl SensorPolling for VisionCharacterSensor {
             };
 
             let mut distance_to_target: f32 = 0.0;
-            let mut character_id: Option<InstanceId> = None;
-            let mut see_point: Option<Vector3> = None;
+            let mut character_id: Option<EntityId> = None;
+            let mut see_point: Option<Vec3> = None;
 
             for raycast_target in raycast_directions {
                 ray_params.set_to(raycast_target);
@@ -92,9 +103,9 @@ impl SensorPolling for VisionCharacterSensor {
                     character_id = intersection_result.get("collider").map(|v| {
                         let area = v.to::<Gd<GodotVisibilityArea3D>>();
                         let instance_id = area.bind().owner.as_ref().unwrap().instance_id();
-                        instance_id
+                        EntityId::from(instance_id)
                     });
-                    see_point = Some(intersection_point);
+                    see_point = Some(intersection_point.into());
                     break;
                 }
             }
@@ -111,7 +122,7 @@ impl SensorPolling for VisionCharacterSensor {
             )));
             // update fact
             if let Some(fact) = args.working_memory.find_fact_mut(fact_query) {
-                fact.update_time = SystemTime::now();
+                fact.update_time = now;
                 previous_stimulation = fact.confidence;
                 fact.confidence = (fact.confidence + detection_strength).min(1.1);
                 current_stimulation = fact.confidence;
@@ -128,30 +139,15 @@ impl SensorPolling for VisionCharacterSensor {
                 current_stimulation = detection_strength;
             }
 
+            // character is fully known once spotted, knowledge fades away when it is out of sight
+            let knowledge =
+                WMProperty::Knowledge(Knowledge::Character(character_id.unwrap(), see_point));
             // new target spotted
             if current_stimulation >= 1.0 && previous_stimulation < 1.0 {
-                let fact_query = FactQuery::with_check(FactQueryCheck::Match(
-                    WMProperty::Knowledge(Knowledge::Character(character_id.unwrap(), None)),
-                ));
-                if let Some(fact) = args.working_memory.find_fact_mut(fact_query) {
-                    // update
-                    let WMProperty::Knowledge(Knowledge::Character(_i, pos)) = &mut fact.f_type
-                    else {
-                        return false;
-                    };
-                    *pos = see_point;
-                    fact.confidence = distance_to_target;
-                    fact.update_time = SystemTime::now();
-                } else {
-                    // new character spotted!!
-                    args.working_memory.add_working_memory_fact(
-                        WMProperty::Knowledge(Knowledge::Character(
-                            character_id.unwrap(),
-                            see_point,
-                        )),
-                        distance_to_target,
-                        240.0,
-                    );
+                let fact_query = FactQuery::with_check(FactQueryCheck::Match(knowledge.clone()));
+                let is_known = args.working_memory.find_fact(fact_query).is_some();
+                args.working_memory.add_or_update(knowledge, 1.0, 240.0);
+                if !is_known {
                     // add desire to be surprised upon spotting new enemy
                     args.working_memory.add_working_memory_fact(
                         WMProperty::Desire(Surprise),
@@ -166,19 +162,8 @@ impl SensorPolling for VisionCharacterSensor {
                     .valid_targets
                     .union(TargetMask::VisibleCharacter);
             } else if current_stimulation > 1.0 {
-                // update character knowledge with latest known position
-                let fact_query = FactQuery::with_check(FactQueryCheck::Match(
-                    WMProperty::Knowledge(Knowledge::Character(character_id.unwrap(), None)),
-                ));
-                let Some(fact) = args.working_memory.find_fact_mut(fact_query) else {
-                    return false;
-                };
-                fact.confidence = distance_to_target;
-                fact.update_time = SystemTime::now();
-                let WMProperty::Knowledge(Knowledge::Character(_i, pos)) = &mut fact.f_type else {
-                    return false;
-                };
-                *pos = see_point;
+                // reinforce character knowledge with latest known position
+                args.working_memory.add_or_update(knowledge, 1.0, 240.0);
             }
         }
         false
@@ -1,3 +1,4 @@
 pub mod target;
 pub(crate) mod target_select_character;
+pub(crate) mod target_select_last_known_position;
 pub mod targeting_systems;
@@ -1,13 +1,10 @@
+use crate::ai::world_state::TargetType;
 use godot::classes::Node3D;
 use godot::prelude::*;
-use serde::{Deserialize, Serialize};
-use strum_macros::EnumDiscriminants;
 
 /// todo – use some generational id instead of instances ids
 
-#[derive(Debug, EnumDiscriminants)]
-#[strum_discriminants(name(TargetType))]
-#[strum_discriminants(derive(Serialize, Deserialize, Hash))]
+#[derive(Debug)]
 pub enum AITarget {
     /// some character target, like a player, with a hitbox position attached
     Character(InstanceId, Option<Vector3>),
@@ -35,3 +32,16 @@ impl AITarget {
         }
     }
 }
+
+impl From<&AITarget> for TargetType {
+    fn from(target: &AITarget) -> Self {
+        match target {
+            AITarget::Character(..) => TargetType::Character,
+            AITarget::CombatOpportunity => TargetType::CombatOpportunity,
+            AITarget::Disturbance(_) => TargetType::Disturbance,
+            AITarget::Interest(_) => TargetType::Interest,
+            AITarget::Object(_) => TargetType::Object,
+            AITarget::SmartObject(_) => TargetType::SmartObject,
+        }
+    }
+}
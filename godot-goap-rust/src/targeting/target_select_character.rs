@@ -13,5 +13,8 @@ pub fn select_character(args: &mut ThinkerProcessArgs) -> Option<AITarget> {
         return None;
     };
     // todo – create some hitpoint selector
-    Some(AITarget::Character(character_id, pos))
+    Some(AITarget::Character(
+        character_id.into(),
+        pos.map(Into::into),
+    ))
 }
@@ -0,0 +1,19 @@
+use crate::ai::working_memory::{
+    FactQuery, FactQueryCheck, Knowledge, WMKnowledgeType, WMProperty,
+};
+use crate::sensors::sensor_types::ThinkerProcessArgs;
+use crate::targeting::target::AITarget;
+
+/// selects the last known position of the lost character as a disturbance worth investigating
+pub fn select_last_known_position(args: &mut ThinkerProcessArgs) -> Option<AITarget> {
+    let fact_query = FactQuery::with_check(FactQueryCheck::Knowledge(
+        WMKnowledgeType::LastTargetPosition,
+    ));
+    let fact = args
+        .working_memory
+        .find_fact_with_max_confidence(fact_query)?;
+    let WMProperty::Knowledge(Knowledge::LastTargetPosition(pos)) = fact.f_type else {
+        return None;
+    };
+    Some(AITarget::Disturbance(pos.into()))
+}
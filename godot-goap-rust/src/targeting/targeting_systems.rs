@@ -1,6 +1,8 @@
+use crate::ai::world_state::TargetType;
 use crate::sensors::sensor_types::ThinkerProcessArgs;
-use crate::targeting::target::{AITarget, TargetType};
+use crate::targeting::target::AITarget;
 use crate::targeting::target_select_character::select_character;
+use crate::targeting::target_select_last_known_position::select_last_known_position;
 use bitflags::bitflags;
 
 pub type TargetSelectorWithMask = (
@@ -22,6 +24,8 @@ bitflags! {
         const Node = 1 << 4;
         const Danger = 1 << 5;
         const CharacterAimingAtMe = 1 << 6;
+        /// last known position of the lost character
+        const LastKnownPosition = 1 << 7;
     }
 }
 
@@ -32,12 +36,19 @@ impl Default for TargetMask {
 }
 
 impl TargetMask {
-    fn priority() -> [TargetSelectorWithMask; 1] {
-        [(
-            TargetMask::VisibleCharacter,
-            TargetType::Character,
-            select_character,
-        )]
+    fn priority() -> [TargetSelectorWithMask; 2] {
+        [
+            (
+                TargetMask::VisibleCharacter,
+                TargetType::Character,
+                select_character,
+            ),
+            (
+                TargetMask::LastKnownPosition,
+                TargetType::Disturbance,
+                select_last_known_position,
+            ),
+        ]
     }
 
     pub fn valid_target_selectors(
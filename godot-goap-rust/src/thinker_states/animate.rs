@@ -1,3 +1,4 @@
+use crate::ai::clock::GameClock;
 use crate::ai::working_memory::Event::AnimationCompleted;
 use crate::ai::working_memory::{FactQuery, FactQueryCheck, WMProperty};
 use crate::thinker_states::types::{StateArguments, ThinkerState};
@@ -5,7 +6,6 @@ use godot::classes::AnimationNodeStateMachinePlayback;
 use godot::prelude::*;
 use serde::{Deserialize, Serialize};
 use std::collections::VecDeque;
-use std::time::SystemTime;
 
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub enum AnimationMode {
@@ -32,7 +32,8 @@ pub struct AnimateState {
     pub name: String,
     pub mode: AnimationMode,
     pub loops_performed: u32,
-    pub creation_time: SystemTime,
+    /// game time of creating this state
+    pub creation_time: f64,
 }
 
 impl AnimateState {
@@ -42,7 +43,7 @@ impl AnimateState {
             name,
             mode,
             loops_performed: 0,
-            creation_time: SystemTime::now(),
+            creation_time: GameClock::global().now(),
         })
     }
 
@@ -89,7 +90,7 @@ impl ThinkerState for AnimateState {
                 self.play(&mut args);
             }
             AnimationMode::Timed(time) => {
-                if self.creation_time.elapsed().unwrap().as_secs_f64() > *time {
+                if GameClock::global().elapsed_since(self.creation_time) > *time {
                     is_finished = true;
                 }
             }
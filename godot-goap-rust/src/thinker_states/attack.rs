@@ -0,0 +1,58 @@
+use crate::animations::animation_data::AnimationProps;
+use crate::thinker_states::animate::AnimateState;
+use crate::thinker_states::types::{StateArguments, ThinkerState};
+use std::collections::VecDeque;
+
+/// plays the steps of an attack one after another.
+/// Animation is marked as completed after the last step.
+#[derive(Debug)]
+pub struct AttackState {
+    pub steps: VecDeque<AnimationProps>,
+    pub current_step: Option<Box<AnimateState>>,
+}
+
+impl AttackState {
+    pub fn new_boxed(steps: impl IntoIterator<Item = AnimationProps>) -> Box<Self> {
+        Box::new(AttackState {
+            steps: steps.into_iter().collect(),
+            current_step: None,
+        })
+    }
+
+    fn next_step(&mut self, args: StateArguments) {
+        let Some(step) = self.steps.pop_front() else {
+            self.current_step = None;
+            args.blackboard.animation_completed = true;
+            return;
+        };
+        args.blackboard.animation_completed = false;
+        let mut new_step = AnimateState::new_boxed(step.tree_name, step.name, step.mode);
+        new_step.enter(args);
+        self.current_step = Some(new_step);
+    }
+}
+
+impl ThinkerState for AttackState {
+    fn exit(&mut self, args: &mut StateArguments) {
+        if let Some(current_step) = self.current_step.as_mut() {
+            current_step.exit(args);
+        }
+    }
+
+    fn enter(&mut self, args: StateArguments) {
+        self.next_step(args);
+    }
+
+    fn physics_process(&mut self, delta: f64, mut args: StateArguments) {
+        let Some(current_step) = self.current_step.as_mut() else {
+            return;
+        };
+        current_step.physics_process(delta, args.reborrow());
+        if args.blackboard.animation_completed {
+            current_step.exit(&mut args);
+            self.next_step(args);
+        }
+    }
+
+    fn update_animation(&mut self, _args: StateArguments) {}
+}
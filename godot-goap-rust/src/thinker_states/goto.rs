@@ -1,11 +1,11 @@
 use crate::ai::blackboard::SpeedMod;
+use crate::ai::clock::GameClock;
 use crate::ai::world_state::{WSProperty, WorldStateProperty};
 use crate::thinker_states::navigation_subsystem::RotationTarget;
 use crate::thinker_states::types::{StateArguments, ThinkerState};
 use godot::builtin::math::ApproxEq;
 use godot::classes::{AnimationNodeStateMachinePlayback, MeshInstance3D};
 use godot::prelude::*;
-use std::time::SystemTime;
 
 #[derive(Debug)]
 pub enum Destination {
@@ -30,7 +30,8 @@ pub struct GotoState {
     pub is_destination_blocked: bool,
     pub finished: bool,
     pub should_repath: bool,
-    pub time_since_last_pathing: SystemTime,
+    /// game time of the last path update
+    pub time_since_last_pathing: f64,
 }
 
 impl GotoState {
@@ -41,7 +42,7 @@ impl GotoState {
             is_destination_blocked: false,
             finished: false,
             should_repath: false,
-            time_since_last_pathing: SystemTime::now(),
+            time_since_last_pathing: GameClock::global().now(),
         };
         Box::new(state)
     }
@@ -93,14 +94,9 @@ impl ThinkerState for GotoState {
         };
 
         if self.should_repath
-            && (self
-                .time_since_last_pathing
-                .elapsed()
-                .unwrap()
-                .as_secs_f64()
-                > 0.25)
+            && GameClock::global().elapsed_since(self.time_since_last_pathing) > 0.25
         {
-            self.time_since_last_pathing = SystemTime::now();
+            self.time_since_last_pathing = GameClock::global().now();
             nav_agent.set_target_position(self.get_target_pos());
         }
 
@@ -1,8 +1,10 @@
 pub mod animate;
+pub mod attack;
 mod character_utils;
 pub mod goto;
 pub(crate) mod navigation_subsystem;
 pub(crate) mod polling;
 pub mod process_thinker;
+pub mod search;
 pub mod types;
-mod use_ai_node;
+pub mod use_ai_node;
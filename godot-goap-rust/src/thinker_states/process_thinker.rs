@@ -2,7 +2,8 @@ use crate::ai::thinker::Thinker;
 use crate::ai::world_state::WSProperty::{Target, Truth};
 use crate::ai::world_state::WorldStateProperty;
 use crate::ai_nodes::ai_node::AINode;
-use crate::sensors::sensor_types::{SensorPolling, ThinkerProcessArgs};
+use crate::attacks::process_attacks::process_attacks;
+use crate::sensors::sensor_types::{SensorEvent, SensorPolling, ThinkerProcessArgs};
 use crate::targeting::targeting_systems::TargetMask;
 use crate::thinker_states::navigation_subsystem::{navigate, NavigationArguments};
 use crate::thinker_states::polling::PollingResult;
@@ -22,6 +23,10 @@ pub fn process_thinker(
         panic!("couldn't open thinker mutex!")
     };
     let shared = &mut *shared_guard;
+    shared.blackboard.health = base.bind().damage_receptor.as_ref().map(|receptor| {
+        let receptor = receptor.bind();
+        (receptor.hp / receptor.max_hp.max(f64::EPSILON)) as f32
+    });
     let mut sensor_args = ThinkerProcessArgs {
         id: thinker.id,
         character_rid: base.bind().character_body.as_ref().unwrap().get_rid(),
@@ -45,6 +50,16 @@ pub fn process_thinker(
         ainodes,
     };
 
+    // deliver queued stimuli to the event sensors before polling, so they can be reacted to this very frame.
+    // Each stimulus is delivered until some sensor consumes it.
+    for stimulus in thinker.stimuli.drain(..) {
+        for sensor in thinker.event_sensor.iter_mut() {
+            if sensor.stimulate(&stimulus, &mut sensor_args) {
+                break;
+            }
+        }
+    }
+
     // run polling sensors
     // todo – benchmark if rayon wouldn't do a job faster
     for sensor in thinker.polling_sensors.iter_mut() {
@@ -68,6 +83,13 @@ pub fn process_thinker(
             sensor_args.world_state[WorldStateProperty::HasTarget] = Some(Truth(false));
         }
     }
+    // update available attacks
+    process_attacks(
+        &thinker.attacks,
+        sensor_args.world_state,
+        sensor_args.working_memory,
+        sensor_args.blackboard,
+    );
 
     // state change
     let new_bb_state = shared.blackboard.new_state.take();
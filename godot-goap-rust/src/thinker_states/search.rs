@@ -0,0 +1,85 @@
+use crate::ai::world_state::{WSProperty, WorldStateProperty};
+use crate::thinker_states::goto::{Destination, GotoState};
+use crate::thinker_states::types::{StateArguments, ThinkerState};
+use godot::classes::NavigationServer3D;
+use godot::prelude::*;
+use rand::{rng, Rng};
+use std::f32::consts::TAU;
+
+/// searches the area by walking to the random points of the navigation mesh around its center
+#[derive(Debug)]
+pub struct SearchState {
+    pub center: Vector3,
+    pub radius: f32,
+    pub animation_name: String,
+    /// game time at which the search ends
+    pub ends_at: f64,
+    /// walk to the currently searched point
+    current_leg: Option<Box<GotoState>>,
+}
+
+impl SearchState {
+    pub fn new_boxed(
+        animation_name: String,
+        center: Vector3,
+        radius: f32,
+        ends_at: f64,
+    ) -> Box<Self> {
+        let state = SearchState {
+            center,
+            radius,
+            animation_name,
+            ends_at,
+            current_leg: None,
+        };
+        Box::new(state)
+    }
+
+    /// closest point of the navigation mesh to some random point within the searched area
+    fn sample_point(&self, args: &StateArguments) -> Vector3 {
+        let mut rng = rng();
+        let angle = rng.random_range(0.0..TAU);
+        let distance = self.radius * rng.random_range(0.0f32..1.0).sqrt();
+        let point = self.center + Vector3::new(angle.cos(), 0.0, angle.sin()) * distance;
+        let Some(map_rid) = args
+            .base
+            .bind()
+            .navigation_agent
+            .as_ref()
+            .map(|agent| agent.get_navigation_map())
+        else {
+            return self.center;
+        };
+        NavigationServer3D::singleton().map_get_closest_point(map_rid, point)
+    }
+}
+
+impl ThinkerState for SearchState {
+    fn exit(&mut self, args: &mut StateArguments) {
+        if let Some(mut leg) = self.current_leg.take() {
+            leg.exit(args);
+        }
+    }
+
+    fn physics_process(&mut self, delta: f64, mut args: StateArguments) {
+        if args.working_memory.now() >= self.ends_at {
+            if let Some(mut leg) = self.current_leg.take() {
+                leg.exit(&mut args);
+            }
+            args.world_state[WorldStateProperty::IsAreaSurveyed] = Some(WSProperty::Truth(true));
+            return;
+        }
+        let is_leg_finished = self.current_leg.as_ref().is_none_or(|leg| leg.finished);
+        if is_leg_finished {
+            let destination = Destination::Position(self.sample_point(&args));
+            let mut leg = GotoState::new_boxed(self.animation_name.clone(), destination);
+            leg.enter(args.reborrow());
+            self.current_leg = Some(leg);
+        }
+        if let Some(leg) = self.current_leg.as_mut() {
+            leg.physics_process(delta, args);
+        }
+    }
+
+    fn update_animation(&mut self, _args: StateArguments) {}
+}
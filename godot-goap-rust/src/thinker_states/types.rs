@@ -13,6 +13,19 @@ pub struct StateArguments<'a> {
     pub blackboard: &'a mut Blackboard,
 }
 
+impl StateArguments<'_> {
+    /// arguments borrowed for a shorter lifetime, passed down to the nested states
+    pub fn reborrow(&mut self) -> StateArguments<'_> {
+        StateArguments {
+            base: self.base.clone(),
+            delta: self.delta,
+            world_state: &mut *self.world_state,
+            working_memory: &mut *self.working_memory,
+            blackboard: &mut *self.blackboard,
+        }
+    }
+}
+
 pub trait ThinkerState: Debug {
     fn exit(&mut self, _args: &mut StateArguments) {}
     fn enter(&mut self, _args: StateArguments) {}
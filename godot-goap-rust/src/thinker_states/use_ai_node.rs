@@ -1 +1,136 @@
+use crate::act_react::act_react_executor::ActReactExecutor;
+use crate::act_react::act_react_resource::Emitter;
+use crate::ai::world_state::{NodeTypeEnum, WSProperty, WorldStateProperty};
+use crate::ai_nodes::ai_node::SmartObjectInteraction;
+use crate::ai_nodes::godot_ai_node::GodotAINode;
+use crate::godot_api::gamesys::GameSystem;
+use crate::godot_api_acts::frob_resource::ActFrob;
+use crate::thinker_states::animate::{AnimateState, AnimationMode};
+use crate::thinker_states::goto::{Destination, GotoState};
+use crate::thinker_states::navigation_subsystem::RotationTarget;
+use crate::thinker_states::types::{StateArguments, ThinkerState};
+use godot::prelude::*;
 
+/// walks to the SmartObject node, plays its animation & fires its reaction once the animation is completed
+#[derive(Debug)]
+pub struct UseAINodeState {
+    /// instance id of the used GodotAINode
+    pub ainode_base_id: InstanceId,
+    pub interaction: SmartObjectInteraction,
+    walk: Option<Box<GotoState>>,
+    animate: Option<Box<AnimateState>>,
+}
+
+impl UseAINodeState {
+    pub fn new_boxed(
+        walk_animation_name: String,
+        ainode_base_id: InstanceId,
+        position: Vector3,
+        interaction: SmartObjectInteraction,
+    ) -> Box<Self> {
+        let state = UseAINodeState {
+            ainode_base_id,
+            interaction,
+            walk: Some(GotoState::new_boxed(
+                walk_animation_name,
+                Destination::Position(position),
+            )),
+            animate: None,
+        };
+        Box::new(state)
+    }
+
+    fn start_animation(&mut self, args: &mut StateArguments) {
+        if let Some(object) = self
+            .interaction
+            .object
+            .and_then(|id| Gd::<Node3D>::try_from_instance_id(id).ok())
+        {
+            args.blackboard.rotation_target =
+                Some(RotationTarget::Position(object.get_global_position()));
+        }
+        let mode = if self.interaction.duration > 0.0 {
+            AnimationMode::Timed(self.interaction.duration)
+        } else {
+            AnimationMode::OneShot
+        };
+        args.blackboard.animation_completed = false;
+        let mut animate = AnimateState::new_boxed(
+            self.interaction.animation.clone(),
+            self.interaction.animation.clone(),
+            mode,
+        );
+        animate.enter(args.reborrow());
+        self.animate = Some(animate);
+    }
+
+    /// frobs the used object
+    fn react(&self, args: &StateArguments) {
+        let Ok(ainode) = Gd::<GodotAINode>::try_from_instance_id(self.ainode_base_id) else {
+            return;
+        };
+        let Some(reaction) = ainode.bind().use_reaction.clone() else {
+            return;
+        };
+        let reactor = ainode
+            .bind()
+            .animatable_object
+            .clone()
+            .map(|object| object.upcast::<Node>())
+            .unwrap_or_else(|| ainode.clone().upcast::<Node>());
+        let context = dict! {
+            "actor": args.base.clone(),
+            "reactor": reactor,
+        };
+        let act = ActFrob::new_gd()
+            .into_dyn::<dyn Emitter>()
+            .upcast::<Resource>();
+        ActReactExecutor::singleton()
+            .bind_mut()
+            .react_single(act, reaction, context);
+    }
+}
+
+impl ThinkerState for UseAINodeState {
+    fn exit(&mut self, args: &mut StateArguments) {
+        if let Some(mut walk) = self.walk.take() {
+            walk.exit(args);
+        }
+        if let Some(mut animate) = self.animate.take() {
+            animate.exit(args);
+        }
+    }
+
+    fn enter(&mut self, args: StateArguments) {
+        if let Some(walk) = self.walk.as_mut() {
+            walk.enter(args);
+        }
+    }
+
+    fn physics_process(&mut self, delta: f64, mut args: StateArguments) {
+        if let Some(walk) = self.walk.as_mut() {
+            walk.physics_process(delta, args.reborrow());
+            if !walk.finished {
+                return;
+            }
+            walk.exit(&mut args);
+            self.walk = None;
+            self.start_animation(&mut args);
+            return;
+        }
+        let Some(animate) = self.animate.as_mut() else {
+            return;
+        };
+        animate.physics_process(delta, args.reborrow());
+        if !args.blackboard.animation_completed {
+            return;
+        }
+        animate.exit(&mut args);
+        self.animate = None;
+        self.react(&args);
+        args.world_state[WorldStateProperty::AtNodeType] =
+            Some(WSProperty::Node(NodeTypeEnum::SmartObject));
+    }
+
+    fn update_animation(&mut self, _args: StateArguments) {}
+}